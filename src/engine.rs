@@ -0,0 +1,75 @@
+use std::sync::Arc;
+
+use crate::*;
+use runtime::eval;
+use runtime::rules::{RuleSet, RULE_SHEET};
+use runtime::scope::{Native, Scope};
+use runtime::solve;
+
+/// an evaluator with its own variables, functions and settings, for embedding vac
+#[derive(Debug, Clone)]
+pub struct Engine {
+    scope: Scope,
+}
+impl Engine {
+    pub fn new() -> Result<Self, Error> {
+        let mut scope = Scope::new();
+        scope.rules = Arc::new(RuleSet::parse(RULE_SHEET)?);
+        Ok(Self { scope })
+    }
+    /// all syntax errors of the input at once, with the calls of the rest checked, or the first error while evaluating it
+    pub fn eval_str(&mut self, input: &str) -> Result<Return, Vec<Error>> {
+        let (tokens, mut errors) = scanning::lexer::lex_all(input);
+        let (expr, parse_errors) = scanning::parser::parse_all(tokens);
+        errors.extend(parse_errors);
+        if !errors.is_empty() {
+            // input with syntax errors is not run, not even in part
+            eval::check(&expr, &self.scope, &mut errors);
+            errors.sort_by_key(|e| e.span.map(|span| span.start));
+            return Err(errors)
+        }
+        let ret = eval::eval(&expr, &mut self.scope).map_err(|e| vec![e])?;
+        // rewritten expressions are not part of the input anymore
+        let ret = eval::simplify(ret, &mut self.scope).map_err(|e| vec![e.unspanned()])?;
+        solve::equation(ret, &mut self.scope).map_err(|e| vec![e.unspanned()])
+    }
+    /// evaluates to a real number, anything else is a type mismatch
+    pub fn eval_number(&mut self, input: &str) -> Result<f64, Vec<Error>> {
+        match self.eval_str(input)? {
+            Return::Value(value) if value.number().is_some() => Ok(value.number().unwrap_or(f64::NAN)),
+            ret => Err(vec![Error::new(ErrorKind::TypeMismatch, format!("expected a number, got {ret}"))])
+        }
+    }
+    /// a command without its leading `:`, like `format sci 6`, with the text to show if there is any
+    pub fn command(&mut self, input: &str) -> Result<Option<String>, Error> {
+        let mut words = input.split_whitespace();
+        match (words.next(), words.next()) {
+            (Some("symbolic"), Some("on")) => self.scope.symbolic = true,
+            (Some("symbolic"), Some("off")) => self.scope.symbolic = false,
+            (Some("symbolic"), None) => return Ok(Some(format!("symbolic {}", if self.scope.symbolic { "on" } else { "off" }))),
+            (Some("format"), None) => return Ok(Some(self.scope.format.to_string())),
+            (Some("format"), Some(arg)) => self.scope.format.set(&[[arg].as_slice(), &words.collect::<Vec<&str>>()].concat())?,
+            _ => return Err(Error::new(ErrorKind::Command, format!("unknown command {:?}", input.trim()))
+                .note("the commands are `:symbolic on|off` and `:format`".to_string()))
+        }
+        Ok(None)
+    }
+    pub fn set_var(&mut self, id: &str, value: impl Into<Value>) {
+        self.scope.set(id.to_string(), Return::Value(value.into()));
+    }
+    pub fn get_var(&self, id: &str) -> Option<&Return> {
+        self.scope.get(id)
+    }
+    /// `f` is called with the evaluated arguments, symbolic arguments keep the call symbolic
+    pub fn register_function(&mut self, id: &str, f: impl Fn(&[Value]) -> Result<Value, Error> + Send + Sync + 'static) {
+        self.scope.set_native(id.to_string(), Native(Arc::new(f)));
+    }
+    /// a result as the REPL prints it, with the format settings of this engine
+    pub fn display(&self, ret: &Return) -> String {
+        match ret {
+            Return::Value(value) => value.format(&self.scope.format),
+            Return::Expr(expr) => expr.format(&self.scope.format),
+            Return::None => String::new(),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::fmt::Display;
+
+use crate::scanning::span::Span;
+
+/// what went wrong, so callers can react without parsing messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    Lex,
+    Parse,
+    /// an operator or function got a value of the wrong type, or nothing
+    TypeMismatch,
+    /// the value is outside of where the operation is defined, like a negative factorial
+    Domain,
+    /// a numeric result still depends on an unknown
+    UnboundName,
+    Arity,
+    Unit,
+    /// a configured bound like the maximum range or call depth is exceeded
+    Limit,
+    /// differentiating or solving something there is no method for
+    Unsupported,
+    Command,
+}
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Lex => "lex error",
+            Self::Parse => "parse error",
+            Self::TypeMismatch => "type mismatch",
+            Self::Domain => "domain error",
+            Self::UnboundName => "unbound name",
+            Self::Arity => "wrong number of arguments",
+            Self::Unit => "unit error",
+            Self::Limit => "limit exceeded",
+            Self::Unsupported => "unsupported",
+            Self::Command => "command error",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// a message, the part of the input it is about if known, and hints on how to fix it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub message: String,
+    pub span: Option<Span>,
+    pub notes: Vec<String>,
+}
+impl Error {
+    pub fn new(kind: ErrorKind, message: String) -> Self {
+        Self { kind, message, span: None, notes: vec![] }
+    }
+    /// the first known span sticks, so the innermost expression is blamed
+    pub fn at(mut self, span: Span) -> Self {
+        if self.span.is_none() && !span.is_empty() { self.span = Some(span) }
+        self
+    }
+    /// forgets the span, for errors in expressions that were not parsed from the current input
+    pub fn unspanned(mut self) -> Self {
+        self.span = None;
+        self
+    }
+    pub fn note(mut self, note: String) -> Self {
+        self.notes.push(note);
+        self
+    }
+    /// the column of the span in `source`, counted in characters from 1
+    pub fn column(&self, source: &str) -> Option<usize> {
+        let span = self.span?;
+        Some(source.get(..span.start)?.chars().count() + 1)
+    }
+    /// the message followed by `source` with the span underlined and the notes
+    pub fn render(&self, source: &str) -> String {
+        let source = source.trim_end();
+        let mut rendered = self.message.clone();
+        if let (Some(span), Some(column)) = (self.span, self.column(source)) {
+            let width = source.get(span.start..span.end.min(source.len())).map_or(1, |range| range.chars().count().max(1));
+            rendered += &format!("\n  {source}\n  {}{}", " ".repeat(column - 1), "^".repeat(width));
+        }
+        for note in self.notes.iter() {
+            rendered += &format!("\n  = help: {note}");
+        }
+        rendered
+    }
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+impl std::error::Error for Error {}
@@ -0,0 +1,30 @@
+//! a math expression evaluator, `Engine` evaluates input like the `vac` shell does
+extern crate logos;
+
+// crate-private, the modules declared after it see it by textual scope
+macro_rules! error {
+    ($kind:ident, $msg:expr, $($a:expr),*) => {
+        Err($crate::Error::new($crate::ErrorKind::$kind, format!($msg, $($a),*)))
+    };
+    ($kind:ident, $msg:expr) => {
+        Err($crate::Error::new($crate::ErrorKind::$kind, format!($msg)))
+    };
+}
+
+mod error;
+mod engine;
+mod scanning;
+mod runtime;
+pub use error::{Error, ErrorKind};
+pub use engine::Engine;
+pub use runtime::eval::Return;
+pub use runtime::value::Value;
+// the types that values and results are made of are part of the API too
+pub use runtime::units::{Dimension, Unit};
+pub use runtime::value::Type;
+pub use scanning::expr::{Expr, ExprKind};
+pub use scanning::span::Span;
+pub use scanning::token::Token;
+pub use num_bigint::BigInt;
+pub use num_complex::Complex64;
+pub use num_rational::BigRational;
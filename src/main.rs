@@ -1,54 +1,68 @@
-#![allow(unused)]
-extern crate logos;
-mod scanning;
-mod runtime;
 use std::{
-    env,
+    env, fs, process,
     io::{stdin, stdout, Write}
 };
+use vac::{Engine, Error, Return};
 
-#[macro_export]
-macro_rules! error {
-    ($msg:expr, $($a:expr),*) => {
-        Err(format!($msg, $($a),*))
-    };
-    ($msg:expr) => {
-        Err(format!($msg))
-    };
+/// a `:command` or an expression
+fn run(input: &str, engine: &mut Engine) -> Result<Option<String>, Vec<Error>> {
+    if let Some(input) = input.trim_start().strip_prefix(':') {
+        return engine.command(input).map_err(|e| vec![e])
+    }
+    match engine.eval_str(input)? {
+        Return::None => Ok(None),
+        ret => Ok(Some(engine.display(&ret)))
+    }
 }
-type Error = String;
 
-fn run(input: String) -> runtime::eval::EvalResult {
-    let tokens = scanning::lexer::lex(input)?;
-    // println!("{tokens:?}");
-    let expr = scanning::parser::parse(tokens)?;
-    // println!("{expr}");
-    // dbg!(expr.count());
-    Ok(runtime::eval::eval(&expr)?)
+fn run_file(path: &str, engine: &mut Engine) -> bool {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) => { eprintln!("{path}: {e}"); return false }
+    };
+    let mut ok = true;
+    for (ln, line) in source.lines().enumerate() {
+        if line.trim().is_empty() { continue }
+        match run(line, engine) {
+            Ok(output) => if let Some(output) = output { println!("{output}") }
+            Err(errors) => {
+                for e in errors.iter() {
+                    match e.column(line) {
+                        Some(column) => eprintln!("{path}:{}:{column}: {}", ln + 1, e.render(line)),
+                        None => eprintln!("{path}:{}: {}", ln + 1, e.render(line))
+                    }
+                }
+                ok = false;
+            }
+        }
+    }
+    ok
 }
 
 fn main() {
-    let Ok(program_path) = env::current_exe() else { eprintln!("can't resolve program path"); return };
     let args: Vec<String> = env::args().collect();
+    let mut engine = match Engine::new() {
+        Ok(engine) => engine,
+        Err(e) => { eprintln!("{e}"); return }
+    };
     let mut args = args.iter();
     args.next();
-    if let Some(arg) = args.next() {
-        todo!("execute file?")
+    if let Some(path) = args.next() {
+        if !run_file(path, &mut engine) { process::exit(1) }
     } else {
         println!("This is the Vac shell.");
         loop {
             let mut input = String::new();
             print!("> ");
-            stdout().flush();
+            if stdout().flush().is_err() { break }
             let Ok(len) = stdin().read_line(&mut input) else { break };
-            match run(input) {
-                Ok(ret) => match ret {
-                    runtime::eval::Return::Expr(expr) => println!("{expr}"),
-                    runtime::eval::Return::Value(value) => println!("{value}"),
-                    runtime::eval::Return::None => {},
+            if len == 0 { break }
+            match run(&input, &mut engine) {
+                Ok(output) => if let Some(output) = output { println!("{output}") }
+                Err(errors) => for e in errors.iter() {
+                    eprintln!("{}", e.render(&input))
                 }
-                Err(e) => eprintln!("{e}")
             }
         }
     }
-}
\ No newline at end of file
+}
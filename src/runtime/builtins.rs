@@ -0,0 +1,284 @@
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_traits::{FromPrimitive, Signed, Zero};
+
+use crate::*;
+use runtime::units::{Unit, UnitError};
+use runtime::value::Value;
+use scanning::token::Token;
+
+pub enum Builtin {
+    /// applied to every element of a vector or set
+    Unary(fn(f64) -> f64),
+    /// applied element-wise like `Value::binary`
+    Binary(fn(f64, f64) -> Result<f64, Error>),
+    /// folded over all arguments, or over the elements of a single vector or set
+    Fold(fn(f64, f64) -> Result<f64, Error>),
+    /// folded like `Fold`, but exactly over integers
+    IntegerFold(fn(BigInt, BigInt) -> BigInt),
+}
+impl Builtin {
+    /// the number of arguments, folds take any
+    pub fn arity(&self) -> Option<usize> {
+        match self {
+            Self::Unary(_) => Some(1),
+            Self::Binary(_) => Some(2),
+            Self::Fold(_) | Self::IntegerFold(_) => None
+        }
+    }
+    pub fn call(&self, id: &str, args: &[Value]) -> Result<Value, Error> {
+        match self {
+            Self::Unary(f) => match args {
+                [value] => value.each(&|value| unary(id, *f, value)),
+                _ => error!(Arity, "expected 1 argument for `{id}`, got {}", args.len())
+            }
+            Self::Binary(f) => match args {
+                [left, right] => left.zip(right, &|x, y| f(x, y)),
+                _ => error!(Arity, "expected 2 arguments for `{id}`, got {}", args.len())
+            }
+            Self::Fold(f) => {
+                let mut acc = None;
+                for value in elements(args) {
+                    let Some(x) = value.number() else { return error!(TypeMismatch, "expected number for `{id}`, got {}", value.typ()) };
+                    acc = Some(match acc {
+                        Some(acc) => f(acc, x)?,
+                        None => x
+                    });
+                }
+                match acc {
+                    Some(acc) => Ok(Value::Number(acc)),
+                    None => error!(Arity, "expected at least 1 argument for `{id}`")
+                }
+            }
+            Self::IntegerFold(f) => {
+                let mut acc = None;
+                for value in elements(args) {
+                    let x = match value {
+                        Value::Integer(x) | Value::Radix(x, _) => x.clone(),
+                        value => match value.number().filter(|x| x.fract() == 0.).and_then(BigInt::from_f64) {
+                            Some(x) => x,
+                            None => return error!(TypeMismatch, "expected integer for `{id}`, got {value}")
+                        }
+                    };
+                    acc = Some(match acc {
+                        Some(acc) => f(acc, x),
+                        None => x.abs()
+                    });
+                }
+                match acc {
+                    Some(acc) => Ok(Value::Integer(acc)),
+                    None => error!(Arity, "expected at least 1 argument for `{id}`")
+                }
+            }
+        }
+    }
+}
+
+/// the arguments of a fold, or the elements of a single vector or set
+fn elements(args: &[Value]) -> Vec<&Value> {
+    match args {
+        [Value::Vector(values, _)] => values.iter().collect(),
+        [Value::Set(values, _)] => values.iter().collect(),
+        _ => args.iter().collect()
+    }
+}
+
+/// complex arguments, and real ones outside of the real domain, use the complex branch
+fn unary(id: &str, f: fn(f64) -> f64, value: &Value) -> Result<Value, Error> {
+    if let Value::Quantity(magnitude, unit) = value {
+        return quantity(id, f, magnitude, unit)
+    }
+    if let Value::Complex(z) = value {
+        return match complex(id, *z) {
+            Some(z) => Ok(Value::complex(z)),
+            None => error!(Domain, "`{id}` is not defined for the complex number {value}")
+        }
+    }
+    let Some(x) = value.number() else { return error!(TypeMismatch, "expected number for `{id}`, got {}", value.typ()) };
+    let y = f(x);
+    match complex(id, Complex64::new(x, 0.)) {
+        Some(z) if y.is_nan() && !x.is_nan() => Ok(Value::complex(z)),
+        _ => Ok(Value::Number(y))
+    }
+}
+
+/// builtins that keep the unit apply to the magnitude, `sqrt` also takes the root of the unit
+fn quantity(id: &str, f: fn(f64) -> f64, magnitude: &Value, unit: &Unit) -> Result<Value, Error> {
+    match id {
+        "abs" | "floor" | "ceil" | "round" | "trunc" | "re" | "im" | "conj" => Ok(Value::quantity(unary(id, f, magnitude)?, unit.clone())),
+        "sign" => unary(id, f, magnitude),
+        "sqrt" => match unit.dimension.root(2) {
+            Some(dimension) => Ok(Value::quantity(magnitude.binary(&Value::exact(unit.scale.clone()), &Token::Mult)?.sqrt()?, Unit::si(dimension))),
+            None => error!(Unit, "cannot take the square root of the unit {}", unit.name)
+        }
+        _ => Err(UnitError::Argument { id: id.to_string(), value: Value::Quantity(Box::new(magnitude.clone()), unit.clone()).to_string() }.into())
+    }
+}
+
+pub fn get(id: &str) -> Option<Builtin> {
+    match id {
+        "sin" => Some(Builtin::Unary(f64::sin)),
+        "cos" => Some(Builtin::Unary(f64::cos)),
+        "tan" => Some(Builtin::Unary(f64::tan)),
+        "asin" => Some(Builtin::Unary(f64::asin)),
+        "acos" => Some(Builtin::Unary(f64::acos)),
+        "atan" => Some(Builtin::Unary(f64::atan)),
+        "sinh" => Some(Builtin::Unary(f64::sinh)),
+        "cosh" => Some(Builtin::Unary(f64::cosh)),
+        "tanh" => Some(Builtin::Unary(f64::tanh)),
+        "asinh" => Some(Builtin::Unary(f64::asinh)),
+        "acosh" => Some(Builtin::Unary(f64::acosh)),
+        "atanh" => Some(Builtin::Unary(f64::atanh)),
+        "exp" => Some(Builtin::Unary(f64::exp)),
+        "ln" => Some(Builtin::Unary(f64::ln)),
+        "log" => Some(Builtin::Unary(f64::log10)),
+        "log2" => Some(Builtin::Unary(f64::log2)),
+        "sqrt" => Some(Builtin::Unary(f64::sqrt)),
+        "cbrt" => Some(Builtin::Unary(f64::cbrt)),
+        "abs" => Some(Builtin::Unary(f64::abs)),
+        "sign" => Some(Builtin::Unary(|x| if x == 0. { 0. } else { x.signum() })),
+        "floor" => Some(Builtin::Unary(f64::floor)),
+        "ceil" => Some(Builtin::Unary(f64::ceil)),
+        "round" => Some(Builtin::Unary(f64::round)),
+        "trunc" => Some(Builtin::Unary(f64::trunc)),
+        "re" => Some(Builtin::Unary(|x| x)),
+        "im" => Some(Builtin::Unary(|_| 0.)),
+        "arg" => Some(Builtin::Unary(|x| if x < 0. { std::f64::consts::PI } else { 0. })),
+        "conj" => Some(Builtin::Unary(|x| x)),
+        "gamma" => Some(Builtin::Unary(gamma)),
+        "digamma" => Some(Builtin::Unary(digamma)),
+        "trigamma" => Some(Builtin::Unary(|x| polygamma(1., x).unwrap_or(f64::NAN))),
+        "polygamma" => Some(Builtin::Binary(polygamma)),
+        "atan2" => Some(Builtin::Binary(|y, x| Ok(y.atan2(x)))),
+        "root" => Some(Builtin::Binary(|x, n| Ok(x.powf(1. / n)))),
+        "min" => Some(Builtin::Fold(|x, y| Ok(x.min(y)))),
+        "max" => Some(Builtin::Fold(|x, y| Ok(x.max(y)))),
+        "gcd" => Some(Builtin::IntegerFold(gcd)),
+        "lcm" => Some(Builtin::IntegerFold(lcm)),
+        _ => None
+    }
+}
+
+/// the complex branch of a unary builtin
+pub fn complex(id: &str, z: Complex64) -> Option<Complex64> {
+    match id {
+        "sin" => Some(z.sin()),
+        "cos" => Some(z.cos()),
+        "tan" => Some(z.tan()),
+        "asin" => Some(z.asin()),
+        "acos" => Some(z.acos()),
+        "atan" => Some(z.atan()),
+        "sinh" => Some(z.sinh()),
+        "cosh" => Some(z.cosh()),
+        "tanh" => Some(z.tanh()),
+        "asinh" => Some(z.asinh()),
+        "acosh" => Some(z.acosh()),
+        "atanh" => Some(z.atanh()),
+        "exp" => Some(z.exp()),
+        "ln" => Some(z.ln()),
+        "log" => Some(z.log10()),
+        "log2" => Some(z.log2()),
+        "sqrt" => Some(z.sqrt()),
+        "cbrt" => Some(z.cbrt()),
+        "abs" => Some(Complex64::new(z.norm(), 0.)),
+        "sign" => Some(if z.norm() == 0. { z } else { z / z.norm() }),
+        "re" => Some(Complex64::new(z.re, 0.)),
+        "im" => Some(Complex64::new(z.im, 0.)),
+        "arg" => Some(Complex64::new(z.arg(), 0.)),
+        "conj" => Some(z.conj()),
+        _ => None
+    }
+}
+
+pub fn constant(id: &str) -> Option<Value> {
+    match id {
+        "pi" => Some(Value::Number(std::f64::consts::PI)),
+        "e" => Some(Value::Number(std::f64::consts::E)),
+        "tau" => Some(Value::Number(std::f64::consts::TAU)),
+        "phi" => Some(Value::Number((1. + 5f64.sqrt()) / 2.)),
+        "inf" => Some(Value::Number(f64::INFINITY)),
+        "nan" => Some(Value::Number(f64::NAN)),
+        "i" => Some(Value::Complex(Complex64::i())),
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        _ => None
+    }
+}
+
+/// Lanczos approximation, reflected for `x < 0.5`
+pub fn gamma(x: f64) -> f64 {
+    const G: f64 = 7.;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8,
+        771.323_428_777_653_1, -176.615_029_162_140_6, 12.507_343_278_686_905,
+        -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6, 1.505_632_735_149_311_6e-7,
+    ];
+    if x.fract() == 0. {
+        return factorial(x - 1.).unwrap_or(f64::NAN)
+    }
+    if x < 0.5 {
+        return std::f64::consts::PI / ((std::f64::consts::PI * x).sin() * gamma(1. - x))
+    }
+    let x = x - 1.;
+    let mut acc = COEFFICIENTS[0];
+    for (i, c) in COEFFICIENTS.iter().enumerate().skip(1) {
+        acc += c / (x + i as f64);
+    }
+    let t = x + G + 0.5;
+    (2. * std::f64::consts::PI).sqrt() * t.powf(x + 0.5) * (-t).exp() * acc
+}
+/// recurrence up to `x >= 10`, then the asymptotic series
+pub fn digamma(x: f64) -> f64 {
+    if x <= 0. && x.fract() == 0. { return f64::NAN }
+    if x < 0.5 {
+        return digamma(1. - x) - std::f64::consts::PI / (std::f64::consts::PI * x).tan()
+    }
+    let (mut x, mut acc) = (x, 0.);
+    while x < 10. {
+        acc -= 1. / x;
+        x += 1.;
+    }
+    let inv = 1. / (x * x);
+    acc + x.ln() - 0.5 / x - inv * (1. / 12. - inv * (1. / 120. - inv * (1. / 252. - inv * (1. / 240. - inv / 132.))))
+}
+/// the `n`th derivative of digamma, by recurrence up to `x >= 20` and the asymptotic series
+pub fn polygamma(n: f64, x: f64) -> Result<f64, Error> {
+    const BERNOULLI: [f64; 5] = [1. / 6., -1. / 30., 1. / 42., -1. / 30., 5. / 66.];
+    if n.fract() != 0. || n < 0. { return error!(Domain, "expected a non-negative integer order for `polygamma`, got {n}") }
+    if n == 0. { return Ok(digamma(x)) }
+    if x <= 0. && x.fract() == 0. { return Ok(f64::NAN) }
+    let factorial = |k: f64| gamma(k + 1.);
+    // (-1)^(n + 1)
+    let sign = if n % 2. == 0. { -1. } else { 1. };
+    let (mut x, mut acc) = (x, 0.);
+    while x < 20. {
+        acc += sign * factorial(n) / x.powf(n + 1.);
+        x += 1.;
+    }
+    let mut series = factorial(n - 1.) / x.powf(n) + factorial(n) / (2. * x.powf(n + 1.));
+    for (k, bernoulli) in BERNOULLI.iter().enumerate() {
+        let k = 2. * (k as f64 + 1.);
+        series += bernoulli * factorial(k + n - 1.) / (factorial(k) * x.powf(k + n));
+    }
+    Ok(acc + sign * series)
+}
+pub fn factorial(x: f64) -> Result<f64, Error> {
+    if x.fract() != 0. { return Ok(gamma(x + 1.)) }
+    if x < 0. { return error!(Domain, "factorial of the negative integer {x} is undefined") }
+    let mut acc = 1f64;
+    let mut i = 2.;
+    while i <= x && acc.is_finite() {
+        acc *= i;
+        i += 1.;
+    }
+    Ok(acc)
+}
+
+fn gcd(mut a: BigInt, mut b: BigInt) -> BigInt {
+    while !b.is_zero() { (a, b) = (b.clone(), a % b); }
+    a.abs()
+}
+fn lcm(a: BigInt, b: BigInt) -> BigInt {
+    if a.is_zero() || b.is_zero() { return BigInt::zero() }
+    (&a / gcd(a.clone(), b.clone()) * b).abs()
+}
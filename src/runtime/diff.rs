@@ -0,0 +1,131 @@
+use crate::*;
+use runtime::eval::{self, EvalResult};
+use runtime::scope::Scope;
+use scanning::expr::{Expr, ExprKind};
+use scanning::token::Token;
+
+/// `diff(expr, x)`, the derivative of `expr` with respect to `x`
+pub fn diff(args: &[Expr], scope: &mut Scope) -> EvalResult {
+    let (expr, x) = match args {
+        [expr, arg] => match &arg.kind {
+            ExprKind::ID(x) => (expr, x),
+            _ => return error!(TypeMismatch, "expected identifier to differentiate by, got {} `{arg}`", arg.name())
+        }
+        _ => return error!(Arity, "expected 2 arguments for `diff`, got {}", args.len())
+    };
+    let expr = eval::eval_symbolic(expr, x, scope)?.expr();
+    let ret = eval::eval(&derivative(&expr, x)?, scope)?;
+    eval::simplify(ret, scope)
+}
+
+fn mult(left: Expr, right: Expr) -> Expr {
+    Expr::binary(left, right, Token::Mult)
+}
+fn div(left: Expr, right: Expr) -> Expr {
+    Expr::binary(left, right, Token::Div)
+}
+fn add(left: Expr, right: Expr) -> Expr {
+    Expr::binary(left, right, Token::Add)
+}
+fn sub(left: Expr, right: Expr) -> Expr {
+    Expr::binary(left, right, Token::Sub)
+}
+fn pow(left: Expr, right: Expr) -> Expr {
+    Expr::binary(left, right, Token::Power)
+}
+fn call(id: &str, arg: Expr) -> Expr {
+    Expr::call(id, vec![arg])
+}
+
+pub fn derivative(expr: &Expr, x: &str) -> Result<Expr, Error> {
+    if !expr.ids().iter().any(|id| id == x) { return Ok(Expr::int(0)) }
+    match &expr.kind {
+        ExprKind::ID(_) => Ok(Expr::int(1)),
+        ExprKind::BinaryOperation { left, right, op } => {
+            let (u, v) = (left.as_ref().clone(), right.as_ref().clone());
+            let (du, dv) = (derivative(&u, x)?, derivative(&v, x)?);
+            match op {
+                Token::Add | Token::Sub | Token::AddSub => Ok(Expr::binary(du, dv, op.clone())),
+                Token::Mult => Ok(add(mult(du, v.clone()), mult(u, dv))),
+                Token::Div => Ok(div(sub(mult(du, v.clone()), mult(u, dv)), pow(v, Expr::int(2)))),
+                // d(u^v) = u^v * (v' ln(u) + v u' / u), with the shortcuts for a constant base or exponent
+                Token::Power if !v.ids().iter().any(|id| id == x) =>
+                    Ok(mult(mult(v.clone(), pow(u, sub(v, Expr::int(1)))), du)),
+                Token::Power if !u.ids().iter().any(|id| id == x) =>
+                    Ok(mult(mult(expr.clone(), call("ln", u)), dv)),
+                Token::Power => Ok(mult(expr.clone(), add(mult(dv, call("ln", u.clone())), div(mult(v, du), u)))),
+                _ => error!(Unsupported, "cannot differentiate the binary operator '{op}'")
+            }
+        }
+        ExprKind::UnaryOperation { expr: u, op } => match op {
+            Token::Sub | Token::Add => Ok(Expr::unary(derivative(u, x)?, op.clone())),
+            _ => error!(Unsupported, "cannot differentiate the unary operator '{op}'")
+        }
+        ExprKind::UnaryOperationRight { expr: u, op } => match op {
+            Token::Percent => Ok(div(derivative(u, x)?, Expr::int(100))),
+            // u! = gamma(u + 1)
+            Token::Fraction => {
+                let u1 = add(u.as_ref().clone(), Expr::int(1));
+                Ok(mult(mult(call("gamma", u1.clone()), call("digamma", u1)), derivative(u, x)?))
+            }
+            _ => error!(Unsupported, "cannot differentiate the unary operator '{op}'")
+        }
+        ExprKind::Absolute(u) => Ok(mult(call("sign", u.as_ref().clone()), derivative(u, x)?)),
+        ExprKind::Vector(exprs) => Ok(Expr::from(ExprKind::Vector(exprs.iter().map(|expr| derivative(expr, x)).collect::<Result<Vec<Expr>, Error>>()?))),
+        ExprKind::Set(exprs) => Ok(Expr::from(ExprKind::Set(exprs.iter().map(|expr| derivative(expr, x)).collect::<Result<Vec<Expr>, Error>>()?))),
+        ExprKind::Call { id, args } => match args.as_slice() {
+            // parts of a complex function of a real variable are differentiated part by part
+            [u] if ["re", "im", "conj"].contains(&id.as_str()) => Ok(call(id, derivative(u, x)?)),
+            // arg(u) = im(ln(u))
+            [u] if id == "arg" => Ok(call("im", div(derivative(u, x)?, u.clone()))),
+            [u] => Ok(mult(outer(id, u.clone())?, derivative(u, x)?)),
+            [n, u] if id == "polygamma" => {
+                if n.ids().iter().any(|id| id == x) { return error!(Unsupported, "cannot differentiate `polygamma` by its order") }
+                Ok(mult(Expr::call("polygamma", vec![add(n.clone(), Expr::int(1)), u.clone()]), derivative(u, x)?))
+            }
+            [y, z] if id == "atan2" => {
+                let (dy, dz) = (derivative(y, x)?, derivative(z, x)?);
+                Ok(div(
+                    sub(mult(z.clone(), dy), mult(y.clone(), dz)),
+                    add(pow(z.clone(), Expr::int(2)), pow(y.clone(), Expr::int(2)))
+                ))
+            }
+            [u, n] if id == "root" => derivative(&pow(u.clone(), div(Expr::int(1), n.clone())), x),
+            _ => error!(Unsupported, "cannot differentiate `{id}` with {} arguments", args.len())
+        }
+        ExprKind::Int(_) | ExprKind::Float(_) | ExprKind::Continue | ExprKind::Unit(_) | ExprKind::Store { .. } | ExprKind::Error => error!(Unsupported, "cannot differentiate {} `{expr}`", expr.name())
+    }
+}
+
+/// derivative of a single argument function at `u`, the chain rule factor is added by the caller
+fn outer(id: &str, u: Expr) -> Result<Expr, Error> {
+    let one = Expr::int(1);
+    let square = pow(u.clone(), Expr::int(2));
+    match id {
+        "sin" => Ok(call("cos", u)),
+        "cos" => Ok(Expr::unary(call("sin", u), Token::Sub)),
+        "tan" => Ok(div(one, pow(call("cos", u), Expr::int(2)))),
+        "asin" => Ok(div(one.clone(), call("sqrt", sub(one, square)))),
+        "acos" => Ok(Expr::unary(div(one.clone(), call("sqrt", sub(one, square))), Token::Sub)),
+        "atan" => Ok(div(one.clone(), add(one, square))),
+        "sinh" => Ok(call("cosh", u)),
+        "cosh" => Ok(call("sinh", u)),
+        "tanh" => Ok(div(one, pow(call("cosh", u), Expr::int(2)))),
+        "asinh" => Ok(div(one.clone(), call("sqrt", add(square, one)))),
+        "acosh" => Ok(div(one.clone(), call("sqrt", sub(square, one)))),
+        "atanh" => Ok(div(one.clone(), sub(one, square))),
+        "exp" => Ok(call("exp", u)),
+        "ln" => Ok(div(one, u)),
+        "log" => Ok(div(one, mult(u, call("ln", Expr::int(10))))),
+        "log2" => Ok(div(one, mult(u, call("ln", Expr::int(2))))),
+        "sqrt" => Ok(div(one, mult(Expr::int(2), call("sqrt", u)))),
+        "cbrt" => Ok(div(one, mult(Expr::int(3), pow(call("cbrt", u), Expr::int(2))))),
+        "abs" => Ok(call("sign", u)),
+        "gamma" => Ok(mult(call("gamma", u.clone()), call("digamma", u))),
+        "digamma" => Ok(call("trigamma", u)),
+        "trigamma" => Ok(Expr::call("polygamma", vec![Expr::int(2), u])),
+        // piecewise constant, the derivative is zero wherever it exists
+        "sign" | "floor" | "ceil" | "round" | "trunc" => Ok(Expr::int(0)),
+        _ => error!(Unsupported, "cannot differentiate `{id}`")
+    }
+}
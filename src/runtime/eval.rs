@@ -2,110 +2,188 @@ use std::collections::HashSet;
 use std::fmt::Display;
 
 use crate::*;
-use runtime::value::Value;
-use scanning::expr::Expr;
+use runtime::builtins;
+use runtime::diff;
+use runtime::integrate;
+use runtime::scope::{Function, Scope};
+use runtime::solve;
+use runtime::sum;
+use runtime::units;
+use runtime::value::{Type, Value, MAX_RANGE};
+use scanning::expr::{Expr, ExprKind};
 use scanning::token::Token;
 
-pub type EvalResult = Result<Return, String>;
+pub type EvalResult = Result<Return, Error>;
 
-pub fn binary_expr(left_left_expr: &Expr, left_right_expr: &Expr, left_op: &Token, right_op: &Token, right_value: &Value) -> Option<EvalResult> {
-    let left_left_ret = eval(left_left_expr);
-    let Ok(left_left_ret) = left_left_ret else {
-        return Some(Err(left_left_ret.err().unwrap()))
-    };
-    let left_right_ret = eval(left_right_expr);
-    let Ok(left_right_ret) = left_right_ret else {
-        return Some(Err(left_right_ret.err().unwrap()))
-    };
-    match (left_left_ret, left_right_ret) {
-        (Return::Expr(left_expr), Return::Value(left_value)) => match (left_op, right_op) {
-            (Token::Add, Token::Add) | (Token::Add, Token::Sub) |
-            (Token::Mult, Token::Mult) | (Token::Mult, Token::Div) => match left_value.binary(right_value, right_op) {
-                Ok(value) => Some(Ok(Return::Expr(Expr::BinaryOperation {
-                    left: Box::new(left_expr), right: Box::new(value.expr()), op: left_op.clone()
-                }))),
-                Err(err) => Some(Err(err))
-            }
-            (Token::Sub, Token::Add) | (Token::Sub, Token::Sub) =>
-            match right_value.unary(left_op) {
-                Ok(right_value) => match left_value.binary(&right_value, right_op) {
-                    Ok(value) => Some(Ok(Return::Expr(Expr::BinaryOperation {
-                        left: Box::new(left_expr), right: Box::new(value.expr()), op: left_op.clone()
-                    }))),
-                    Err(err) => Some(Err(err))
-                }
-                Err(err) => Some(Err(err))
-            }
-            (Token::Div, Token::Mult) | (Token::Div, Token::Div) =>
-            match Value::Number(1.).binary(right_value, left_op) {
-                Ok(right_value) => match left_value.binary(&right_value, right_op) {
-                    Ok(value) => Some(Ok(Return::Expr(Expr::BinaryOperation {
-                        left: Box::new(left_expr), right: Box::new(value.expr()), op: left_op.clone()
-                    }))),
-                    Err(err) => Some(Err(err))
-                }
-                Err(err) => Some(Err(err))
-            }
-            _ => None
-        }
-        (Return::Value(left_value), Return::Expr(left_expr)) => match left_value.binary(right_value, right_op) {
-            Ok(value) => Some(Ok(Return::Expr(Expr::BinaryOperation {
-                left: Box::new(value.expr()), right: Box::new(left_expr), op: left_op.clone()
-            }))),
-            Err(err) => Some(Err(err))
+pub fn binary(left: &Expr, right: &Expr, op: &Token, scope: &mut Scope) -> EvalResult {
+    let left_ret = eval(left, scope)?;
+    let right_ret = eval(right, scope)?;
+    match (left_ret, right_ret) {
+        (Return::Value(left_value), Return::Value(right_value)) => Ok(Return::Value(left_value.binary(&right_value, op)?)),
+        (Return::Expr(left_expr), Return::Expr(right_expr)) => Ok(Return::Expr(Expr::binary(left_expr, right_expr, op.clone()))),
+        (Return::Expr(left_expr), Return::Value(right_value)) => Ok(Return::Expr(Expr::binary(left_expr, right_value.expr(), op.clone()))),
+        (Return::Value(left_value), Return::Expr(right_expr)) => Ok(Return::Expr(Expr::binary(left_value.expr(), right_expr, op.clone()))),
+        _ => error!(TypeMismatch, "expected a value, got nothing")
+    }
+}
+/// `expr -> unit` or `expr -> hex`, symbolic expressions keep the conversion
+pub fn convert(expr: &Expr, target: &Expr, scope: &mut Scope) -> EvalResult {
+    let convert = |value: Value| match &target.kind {
+        ExprKind::Unit(name) => value.convert(&units::parse(name)?),
+        ExprKind::ID(radix) => match radix.as_str() {
+            "hex" => value.radix(16),
+            "oct" => value.radix(8),
+            "bin" => value.radix(2),
+            "dec" => value.radix(10),
+            _ => error!(Unsupported, "unknown base `{radix}`, expected hex, oct, bin or dec")
         }
-        _ => None
+        _ => error!(TypeMismatch, "expected a unit or base to convert to, got {} `{target}`", target.name())
+    };
+    match eval(expr, scope)? {
+        Return::Value(value) => Ok(Return::Value(convert(value)?)),
+        Return::Expr(expr) => Ok(Return::Expr(Expr::binary(expr, target.clone(), Token::Into))),
+        Return::None => error!(TypeMismatch, "expected a value, got nothing")
     }
 }
-
-pub fn binary(left: &Expr, right: &Expr, op: &Token) -> EvalResult {
-    let left_ret = eval(left)?;
-    let right_ret = eval(right)?;
-    match left_ret {
-        Return::Expr(left_expr) => match right_ret {
-            Return::Expr(right_expr) => Ok(Return::Expr(Expr::BinaryOperation { left: Box::new(left_expr), right: Box::new(right_expr), op: op.clone() })),
-            Return::Value(right_value) => match &left_expr {
-                Expr::BinaryOperation { left: left_left_expr, right: left_right_expr, op: left_op } =>
-                    match binary_expr(left_left_expr.as_ref(), left_right_expr.as_ref(), &left_op, op, &right_value) {
-                        Some(res) => res,
-                        None => Ok(Return::Expr(Expr::BinaryOperation { left: Box::new(left_expr), right: Box::new(right_value.expr()), op: op.clone() }))
-                    }
-                _ => Ok(Return::Expr(Expr::BinaryOperation { left: Box::new(left_expr), right: Box::new(right_value.expr()), op: op.clone() }))
-            }
-            Return::None => error!("expected a value, got nothing")
+pub fn unary(expr: &Expr, op: &Token, right: bool, scope: &mut Scope) -> EvalResult {
+    let ret = eval(expr, scope)?;
+    match ret {
+        Return::Expr(expr) if right => Ok(Return::Expr(Expr::from(ExprKind::UnaryOperationRight { expr: Box::new(expr), op: op.clone() }))),
+        Return::Expr(expr) => Ok(Return::Expr(Expr::unary(expr, op.clone()))),
+        Return::Value(value) => Ok(Return::Value(value.unary(op)?)),
+        Return::None => error!(TypeMismatch, "expected a value, got nothing")
+    }
+}
+pub fn absolute(expr: &Expr, scope: &mut Scope) -> EvalResult {
+    let ret = eval(expr, scope)?;
+    match ret {
+        Return::Expr(expr) => Ok(Return::Expr(Expr::from(ExprKind::Absolute(Box::new(expr))))),
+        Return::Value(value) => Ok(Return::Value(value.absolute()?)),
+        Return::None => error!(TypeMismatch, "cannot evaluate the absolute value of {ret}")
+    }
+}
+pub fn store(target: &Expr, expr: &Expr, scope: &mut Scope) -> EvalResult {
+    match &target.kind {
+        ExprKind::ID(id) => {
+            let ret = eval(expr, scope)?;
+            if ret == Return::None { return error!(TypeMismatch, "expected a value, got nothing") }
+            scope.set(id.clone(), ret)
         }
-        Return::Value(left_value) => match right_ret {
-            Return::Expr(right_expr) => match right_expr {
-                
-                _ => Ok(Return::Expr(Expr::BinaryOperation { left: Box::new(left_value.expr()), right: Box::new(right_expr), op: op.clone() }))
+        ExprKind::Call { id, args } => {
+            let mut params = vec![];
+            for arg in args.iter() {
+                let ExprKind::ID(param) = &arg.kind else { return error!(TypeMismatch, "expected identifier as parameter, got {} `{arg}`", arg.name()) };
+                params.push(param.clone());
             }
-            Return::Value(right_value) => Ok(Return::Value(left_value.binary(&right_value, op)?)),
-            Return::None => error!("expected a value, got nothing")
+            scope.set_function(id.clone(), Function { params, body: expr.clone() })
         }
-        Return::None => error!("expected a value, got nothing")
+        _ => return error!(TypeMismatch, "cannot store into {} `{target}`", target.name())
     }
+    Ok(Return::None)
 }
-pub fn unary(expr: &Expr, op: &Token) -> EvalResult {
-    let ret = eval(expr)?;
-    match ret {
-        Return::Expr(expr) => match expr {
-            
-            _ => Ok(Return::Expr(Expr::UnaryOperation { expr: Box::new(expr.clone()), op: op.clone() }))
+pub fn call(id: &str, args: &[Expr], scope: &mut Scope) -> EvalResult {
+    // special forms evaluate expressions that may come from other input, so the call is blamed
+    if scope.get_function(id).is_none() {
+        let ret = match id {
+            "num" => Some(numeric(args, scope)),
+            "solve" => Some(solve::solve(args, scope)),
+            "diff" => Some(diff::diff(args, scope)),
+            "integrate" => Some(integrate::integrate(args, scope)),
+            "sum" => Some(sum::sum(args, Token::Add, scope)),
+            "prod" => Some(sum::sum(args, Token::Mult, scope)),
+            _ => None
+        };
+        if let Some(ret) = ret { return ret.map_err(Error::unspanned) }
+    }
+    let mut rets = vec![];
+    for arg in args.iter() {
+        let ret = eval(arg, scope)?;
+        if ret == Return::None { return error!(TypeMismatch, "expected a value, got nothing") }
+        rets.push(ret);
+    }
+    let Some(function) = scope.get_function(id).cloned() else {
+        let values = rets.iter().filter_map(|ret| match ret {
+            Return::Value(value) => Some(value.clone()),
+            _ => None
+        }).collect::<Vec<Value>>();
+        if values.len() == rets.len() {
+            if let Some(native) = scope.get_native(id) { return Ok(Return::Value((native.0)(&values)?)) }
+            if let Some(builtin) = builtins::get(id) { return Ok(Return::Value(builtin.call(id, &values)?)) }
         }
-        Return::Value(value) => Ok(Return::Value(value.unary(op)?)),
-        Return::None => error!("expected a value, got nothing")
+        return Ok(Return::Expr(Expr::call(id, rets.iter().map(Return::expr).collect())))
+    };
+    if function.params.len() != rets.len() {
+        return Err(Error::new(ErrorKind::Arity, format!("expected {} arguments for `{id}`, got {}", function.params.len(), rets.len()))
+            .note(format!("`{id}` is defined as {id}({})", function.params.join(", "))))
+    }
+    scope.push()?;
+    for (param, ret) in function.params.into_iter().zip(rets) {
+        scope.set(param, ret);
     }
+    let ret = eval(&function.body, scope).map_err(Error::unspanned);
+    scope.pop();
+    ret
 }
-pub fn absolute(expr: &Expr) -> EvalResult {
-    let ret = eval(expr)?;
-    match ret {
-        
-        _ => error!("cannot evaluate the absolute value of {ret}")
+/// the errors in the rest of an input with syntax errors that show without evaluating it, calls with the wrong number of arguments
+pub fn check(expr: &Expr, scope: &Scope, errors: &mut Vec<Error>) {
+    match &expr.kind {
+        ExprKind::ID(_) | ExprKind::Int(_) | ExprKind::Float(_) | ExprKind::Continue | ExprKind::Unit(_) | ExprKind::Error => {}
+        ExprKind::BinaryOperation { left, right, op:_ } | ExprKind::Store { target: left, expr: right } => {
+            check(left, scope, errors);
+            check(right, scope, errors);
+        }
+        ExprKind::UnaryOperation { expr, op:_ } | ExprKind::UnaryOperationRight { expr, op:_ } |
+        ExprKind::Absolute(expr) => check(expr, scope, errors),
+        ExprKind::Vector(exprs) | ExprKind::Set(exprs) => for expr in exprs.iter() { check(expr, scope, errors) }
+        ExprKind::Call { id, args } => {
+            for arg in args.iter() { check(arg, scope, errors) }
+            // registered functions take any number of arguments
+            let arity = match scope.get_function(id) {
+                Some(function) => Some(function.params.len()),
+                None if scope.get_native(id).is_none() => builtins::get(id).and_then(|builtin| builtin.arity()),
+                None => None
+            };
+            match arity {
+                Some(arity) if arity != args.len() => errors.push(Error::new(ErrorKind::Arity,
+                    format!("expected {arity} argument{} for `{id}`, got {}", if arity == 1 { "" } else { "s" }, args.len())).at(expr.span)),
+                _ => {}
+            }
+        }
+    }
+}
+pub fn numeric(args: &[Expr], scope: &mut Scope) -> EvalResult {
+    let [expr] = args else { return error!(Arity, "expected 1 argument for `num`, got {}", args.len()) };
+    let symbolic = scope.symbolic;
+    scope.symbolic = false;
+    // stored expressions may still hold symbolic constants
+    let ret = match eval(expr, scope) {
+        Ok(Return::Expr(expr)) => eval(&expr, scope),
+        ret => ret
+    };
+    scope.symbolic = symbolic;
+    match ret? {
+        Return::Value(value) => Ok(Return::Value(value.float())),
+        ret => Ok(ret)
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Return { Value(Value), Expr(Expr), None }
+impl Return {
+    pub fn value(&self) -> Option<&Value> {
+        match self {
+            Self::Value(value) => Some(value),
+            _ => None
+        }
+    }
+    pub fn expr(&self) -> Expr {
+        match self {
+            Self::Value(value) => value.expr(),
+            Self::Expr(expr) => expr.clone(),
+            Self::None => Expr::from(ExprKind::Set(vec![])),
+        }
+    }
+}
 impl Display for Return {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -116,54 +194,142 @@ impl Display for Return {
     }
 }
 
-pub fn eval(expr: &Expr) -> EvalResult {
-    match expr {
-        Expr::ID(id) => Ok(Return::Expr(expr.clone())),
-        Expr::Int(v) => Ok(Return::Value(Value::Number(*v as f64))),
-        Expr::Float(v) => Ok(Return::Value(Value::Number(*v))),
-        Expr::Continue => error!("unexpected {} `{expr}`", expr.name()),
-        Expr::BinaryOperation { left, right, op } => binary(left.as_ref(), right.as_ref(), op),
-        Expr::UnaryOperation { expr, op } | Expr::UnaryOperationRight { expr, op } => unary(expr.as_ref(), op),
-        Expr::Absolute(expr) => absolute(expr.as_ref()),
-        Expr::Vector(vector) => {
+/// evaluates `expr` with `id` kept symbolic, even if it is bound
+pub fn eval_symbolic(expr: &Expr, id: &str, scope: &mut Scope) -> EvalResult {
+    scope.push()?;
+    scope.set(id.to_string(), Return::Expr(Expr::id(id)));
+    let ret = eval(expr, scope);
+    scope.pop();
+    ret
+}
+/// evaluates `expr` numerically with `id` bound to `v`
+pub fn eval_at(expr: &Expr, id: &str, v: f64, scope: &mut Scope) -> EvalResult {
+    scope.push()?;
+    scope.set(id.to_string(), Return::Value(Value::Number(v)));
+    let symbolic = scope.symbolic;
+    scope.symbolic = false;
+    let ret = eval(expr, scope);
+    scope.symbolic = symbolic;
+    scope.pop();
+    ret
+}
+/// runs the rule set of the scope over a symbolic result
+pub fn simplify(ret: Return, scope: &mut Scope) -> EvalResult {
+    let rules = scope.rules.clone();
+    rules.simplify(ret, scope)
+}
+
+/// evaluates the elements of a vector or set, `...` continues the arithmetic sequence before it
+fn elements(exprs: &[Expr], scope: &mut Scope) -> Result<Vec<Return>, Error> {
+    let mut rets = vec![];
+    let mut exprs = exprs.iter().peekable();
+    while let Some(expr) = exprs.next() {
+        if expr.kind != ExprKind::Continue {
+            let ret = eval(expr, scope)?;
+            if ret == Return::None { return error!(TypeMismatch, "expected a value, got {ret}") }
+            rets.push(ret);
+            continue
+        }
+        let Some(end) = exprs.peek() else { return error!(TypeMismatch, "expected an end after `{expr}`") };
+        let end = match eval(end, scope)? {
+            Return::Value(end) if end.number().is_some() => end,
+            ret => return error!(TypeMismatch, "expected a number as end of sequence, got {ret}")
+        };
+        let (start, step) = match rets.as_slice() {
+            [.., Return::Value(first), Return::Value(start)] if first.number().is_some() && start.number().is_some() =>
+                (start.clone(), start.binary(first, &Token::Sub)?),
+            [.., Return::Value(start)] if start.number().is_some() =>
+                (start.clone(), Value::integer(if end.number() < start.number() { -1 } else { 1 })),
+            _ => return error!(TypeMismatch, "expected a number before `{expr}`")
+        };
+        let count = end.binary(&start, &Token::Sub)?.binary(&step, &Token::Div)?.number().unwrap_or(f64::NAN);
+        if !count.is_finite() || count < 0. {
+            return error!(Domain, "cannot continue the sequence from {start} by {step} to {end}")
+        }
+        if (count - count.round()).abs() > 1e-9 {
+            return error!(Domain, "the sequence from {start} by {step} does not reach {end}")
+        }
+        if count > MAX_RANGE as f64 { return error!(Limit, "the sequence from {start} to {end} is too large") }
+        let mut value = start;
+        for _ in 1..count.round() as usize {
+            value = value.binary(&step, &Token::Add)?;
+            rets.push(Return::Value(value.clone()));
+        }
+    }
+    Ok(rets)
+}
+
+/// errors are blamed on the innermost expression that has a span
+pub fn eval(expr: &Expr, scope: &mut Scope) -> EvalResult {
+    eval_kind(expr, scope).map_err(|e| e.at(expr.span))
+}
+fn eval_kind(expr: &Expr, scope: &mut Scope) -> EvalResult {
+    match &expr.kind {
+        ExprKind::ID(id) => match scope.get(id).cloned() {
+            // stored expressions are evaluated again, so later bindings apply to them too
+            Some(Return::Expr(stored)) if stored.kind != expr.kind => {
+                scope.push()?;
+                let ret = eval(&stored, scope).map_err(Error::unspanned);
+                scope.pop();
+                ret
+            }
+            Some(ret) => Ok(ret),
+            None => match builtins::constant(id) {
+                Some(value) if !scope.symbolic || value.typ() == Type::Bool => Ok(Return::Value(value)),
+                _ => Ok(Return::Expr(expr.clone()))
+            }
+        }
+        ExprKind::Int(v) => Ok(Return::Value(Value::Integer(v.clone()))),
+        ExprKind::Float(v) => Ok(Return::Value(Value::Number(*v))),
+        ExprKind::Continue => error!(TypeMismatch, "unexpected {} `{expr}`", expr.name()),
+        ExprKind::Error => error!(Parse, "cannot evaluate input with syntax errors"),
+        ExprKind::Unit(name) => Ok(Return::Value(Value::quantity(Value::integer(1), units::parse(name)?))),
+        ExprKind::BinaryOperation { left, right, op: Token::Into } => convert(left.as_ref(), right.as_ref(), scope),
+        ExprKind::BinaryOperation { left, right, op } => binary(left.as_ref(), right.as_ref(), op, scope),
+        ExprKind::UnaryOperation { expr, op } => unary(expr.as_ref(), op, false, scope),
+        ExprKind::UnaryOperationRight { expr, op } => unary(expr.as_ref(), op, true, scope),
+        ExprKind::Absolute(expr) => absolute(expr.as_ref(), scope),
+        ExprKind::Call { id, args } => call(id, args, scope),
+        ExprKind::Store { target, expr } => store(target.as_ref(), expr.as_ref(), scope),
+        ExprKind::Vector(vector) => {
+            let rets = elements(vector, scope)?;
+            if rets.iter().any(|ret| matches!(ret, Return::Expr(_))) {
+                return Ok(Return::Expr(Expr::from(ExprKind::Vector(rets.iter().map(Return::expr).collect()))))
+            }
             let mut values = vec![];
             let mut typ = None;
-            for e in vector.iter() {
-                let ret = eval(e)?;
-                if let Return::Value(value) = ret {
-                    if let Some(typ) = &typ {
-                        if typ != &value.typ() {
-                            return error!("expected type {typ}, got {}", value.typ())
-                        }
-                    } else {
-                        typ = Some(value.typ());
+            for ret in rets {
+                let Return::Value(value) = ret else { continue };
+                if let Some(typ) = &typ {
+                    if typ != &value.typ() {
+                        return error!(TypeMismatch, "expected type {typ}, got {}", value.typ())
                     }
-                    values.push(value);
                 } else {
-                    return error!("expected a value, got {ret}")
+                    typ = Some(value.typ());
                 }
+                values.push(value);
             }
             Ok(Return::Value(Value::Vector(values, typ.unwrap())))
         }
-        Expr::Set(set) => {
+        ExprKind::Set(set) => {
+            if set.is_empty() { return Ok(Return::None) }
+            if set.len() == 1 { return eval(&set[0], scope) }
+            let rets = elements(set, scope)?;
+            if rets.iter().any(|ret| matches!(ret, Return::Expr(_))) {
+                return Ok(Return::Expr(Expr::from(ExprKind::Set(rets.iter().map(Return::expr).collect()))))
+            }
             let mut values = HashSet::new();
-            if values.len() == 0 { return Ok(Return::None) }
-            if values.len() == 1 { return eval(&set[0]) }
             let mut typ = None;
-            for e in set.iter() {
-                let ret = eval(e)?;
-                if let Return::Value(value) = ret {
-                    if let Some(typ) = &typ {
-                        if typ != &value.typ() {
-                            return error!("expected type {typ}, got {}", value.typ())
-                        }
-                    } else {
-                        typ = Some(value.typ());
+            for ret in rets {
+                let Return::Value(value) = ret else { continue };
+                if let Some(typ) = &typ {
+                    if typ != &value.typ() {
+                        return error!(TypeMismatch, "expected type {typ}, got {}", value.typ())
                     }
-                    values.insert(value);
                 } else {
-                    return error!("expected a value, got {ret}")
+                    typ = Some(value.typ());
                 }
+                values.insert(value);
             }
             Ok(Return::Value(Value::Set(values, typ.unwrap())))
         }
@@ -0,0 +1,113 @@
+use std::fmt::Display;
+
+use num_bigint::BigInt;
+
+use crate::*;
+
+pub const MAX_DIGITS: usize = 17;
+pub const MAX_DECIMALS: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Notation {
+    /// the shortest form after rounding, scientific only for very large or small numbers
+    Auto,
+    Fixed,
+    Scientific,
+    Engineering,
+}
+
+/// how floating point numbers are printed, `digits` are decimals for fixed notation and significant digits otherwise,
+/// exact integers and fractions are only grouped
+#[derive(Debug, Clone, PartialEq)]
+pub struct Format {
+    pub notation: Notation,
+    pub digits: usize,
+    pub separators: bool,
+}
+impl Default for Format {
+    fn default() -> Self {
+        Self { notation: Notation::Auto, digits: 15, separators: false }
+    }
+}
+impl Format {
+    /// applies a `:format` command, like `sci 6` or `sep on`
+    pub fn set(&mut self, args: &[&str]) -> Result<(), Error> {
+        let (notation, digits) = match args {
+            ["sep", "on"] => { self.separators = true; return Ok(()) }
+            ["sep", "off"] => { self.separators = false; return Ok(()) }
+            [notation] => (*notation, None),
+            [notation, digits] => match digits.parse::<usize>() {
+                Ok(digits) => (*notation, Some(digits)),
+                Err(_) => return error!(Command, "expected a number of digits, got {digits:?}")
+            }
+            _ => return error!(Command, "expected `:format auto|fix|sci|eng [digits]` or `:format sep on|off`")
+        };
+        let notation = match notation {
+            "auto" => Notation::Auto,
+            "fix" => Notation::Fixed,
+            "sci" => Notation::Scientific,
+            "eng" => Notation::Engineering,
+            _ => return error!(Command, "unknown notation {notation:?}, expected auto, fix, sci or eng")
+        };
+        let digits = digits.unwrap_or(if notation == Notation::Fixed { 2 } else { Self::default().digits });
+        match notation {
+            Notation::Fixed if digits > MAX_DECIMALS => return error!(Command, "expected at most {MAX_DECIMALS} decimals, got {digits}"),
+            Notation::Auto | Notation::Scientific | Notation::Engineering if !(1..=MAX_DIGITS).contains(&digits) =>
+                return error!(Command, "expected 1 to {MAX_DIGITS} significant digits, got {digits}"),
+            _ => {}
+        }
+        (self.notation, self.digits) = (notation, digits);
+        Ok(())
+    }
+    pub fn number(&self, x: f64) -> String {
+        if !x.is_finite() { return x.to_string() }
+        // rounding to significant digits through the scientific representation
+        let rounded = format!("{:.*e}", self.digits.saturating_sub(1), x);
+        let exponent = rounded.split_once('e').and_then(|(_, exponent)| exponent.parse::<i32>().ok()).unwrap_or(0);
+        match self.notation {
+            Notation::Auto => {
+                let rounded = rounded.parse::<f64>().unwrap_or(x);
+                if rounded != 0. && !(-7..21).contains(&exponent) {
+                    format!("{rounded:e}")
+                } else {
+                    self.group(&rounded.to_string())
+                }
+            }
+            Notation::Fixed => self.group(&format!("{:.*}", self.digits, x)),
+            Notation::Scientific => rounded,
+            Notation::Engineering => {
+                let shift = exponent.rem_euclid(3);
+                let mantissa = rounded.parse::<f64>().unwrap_or(x) / 10f64.powi(exponent - shift);
+                let decimals = (self.digits as i32 - 1 - shift).max(0) as usize;
+                format!("{mantissa:.decimals$}e{}", exponent - shift)
+            }
+        }
+    }
+    pub fn integer(&self, x: &BigInt) -> String {
+        self.group(&x.to_string())
+    }
+    /// separates the thousands of the integer part
+    fn group(&self, number: &str) -> String {
+        if !self.separators { return number.to_string() }
+        let (sign, number) = number.strip_prefix('-').map_or(("", number), |number| ("-", number));
+        let end = number.find(|c: char| !c.is_ascii_digit()).unwrap_or(number.len());
+        let (integer, rest) = number.split_at(end);
+        let mut grouped = String::new();
+        for (i, c) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 { grouped.push(',') }
+            grouped.push(c);
+        }
+        format!("{sign}{grouped}{rest}")
+    }
+}
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let notation = match self.notation {
+            Notation::Auto => "auto",
+            Notation::Fixed => "fix",
+            Notation::Scientific => "sci",
+            Notation::Engineering => "eng",
+        };
+        write!(f, "format {notation} {}, separators {}", self.digits, if self.separators { "on" } else { "off" })
+    }
+}
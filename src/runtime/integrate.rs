@@ -0,0 +1,90 @@
+use crate::*;
+use runtime::eval::{self, EvalResult, Return};
+use runtime::scope::Scope;
+use runtime::value::Value;
+use scanning::expr::{Expr, ExprKind};
+
+pub const TOLERANCE: f64 = 1e-10;
+pub const MAX_DEPTH: usize = 50;
+/// upper bound on integrand evaluations per quadrature
+pub const MAX_EVALUATIONS: usize = 200_000;
+
+/// `integrate(expr, x, a, b)`, the definite integral by adaptive Simpson quadrature
+pub fn integrate(args: &[Expr], scope: &mut Scope) -> EvalResult {
+    let (expr, x, a, b) = match args {
+        [expr, Expr { kind: ExprKind::ID(x), .. }, a, b] => (expr, x, a, b),
+        [_, arg, _, _] => return error!(TypeMismatch, "expected identifier to integrate by, got {} `{arg}`", arg.name()),
+        _ => return error!(Arity, "expected 4 arguments for `integrate`, got {}", args.len())
+    };
+    let (a, b) = (bound(a, scope)?, bound(b, scope)?);
+    let expr = eval::eval_symbolic(expr, x, scope)?.expr();
+    let mut f = |v: f64| -> Result<f64, Error> {
+        match eval::eval_at(&expr, x, v, scope)? {
+            Return::Value(value) if value.number().is_some() => Ok(value.number().unwrap_or(f64::NAN)),
+            Return::Expr(expr) => error!(UnboundName, "cannot integrate `{expr}`, it has other unknowns than {x}"),
+            ret => error!(TypeMismatch, "cannot integrate {ret}, expected a number")
+        }
+    };
+    let integral = match (a.is_finite(), b.is_finite()) {
+        (true, true) => quadrature(&mut f, a, b)?,
+        // infinite bounds are mapped onto finite ones, `x = a + t / (1 - t)` for `t` in `0..1`
+        (true, false) => b.signum() * quadrature(&mut |t| upper(&mut f, a, b.signum(), t), 0., 1.)?,
+        (false, true) => -a.signum() * quadrature(&mut |t| upper(&mut f, b, a.signum(), t), 0., 1.)?,
+        (false, false) => {
+            let sign = if a < b { 1. } else if a > b { -1. } else { return Ok(Return::Value(Value::Number(0.))) };
+            sign * (quadrature(&mut |t| upper(&mut f, 0., 1., t), 0., 1.)? + quadrature(&mut |t| upper(&mut f, 0., -1., t), 0., 1.)?)
+        }
+    };
+    Ok(Return::Value(Value::Number(integral)))
+}
+
+fn bound(expr: &Expr, scope: &mut Scope) -> Result<f64, Error> {
+    match eval::numeric(std::slice::from_ref(expr), scope)? {
+        Return::Value(value) if value.number().is_some() => Ok(value.number().unwrap_or(f64::NAN)),
+        ret => error!(TypeMismatch, "expected a number as integration bound, got {ret}")
+    }
+}
+
+/// integrand of `a..inf` (or `a..-inf` for a negative `direction`) over `t` in `0..1`
+fn upper(f: &mut dyn FnMut(f64) -> Result<f64, Error>, a: f64, direction: f64, t: f64) -> Result<f64, Error> {
+    if t >= 1. { return Ok(0.) }
+    let x = a + direction * t / (1. - t);
+    Ok(f(x)? / ((1. - t) * (1. - t)))
+}
+
+pub fn quadrature(f: &mut dyn FnMut(f64) -> Result<f64, Error>, a: f64, b: f64) -> Result<f64, Error> {
+    let m = (a + b) / 2.;
+    let (fa, fm, fb) = (endpoint(f, a, b)?, f(m)?, endpoint(f, b, a)?);
+    let whole = simpson(a, b, fa, fm, fb);
+    let mut budget = MAX_EVALUATIONS;
+    adaptive(f, a, b, fa, fm, fb, whole, TOLERANCE, MAX_DEPTH, &mut budget)
+}
+
+/// singular endpoints are sampled slightly inside the interval
+fn endpoint(f: &mut dyn FnMut(f64) -> Result<f64, Error>, a: f64, b: f64) -> Result<f64, Error> {
+    let fa = f(a)?;
+    if fa.is_finite() { return Ok(fa) }
+    f(a + (b - a) * f64::EPSILON.sqrt())
+}
+
+fn simpson(a: f64, b: f64, fa: f64, fm: f64, fb: f64) -> f64 {
+    (b - a) / 6. * (fa + 4. * fm + fb)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn adaptive(f: &mut dyn FnMut(f64) -> Result<f64, Error>, a: f64, b: f64, fa: f64, fm: f64, fb: f64, whole: f64, tolerance: f64, depth: usize, budget: &mut usize) -> Result<f64, Error> {
+    let m = (a + b) / 2.;
+    let (lm, rm) = ((a + m) / 2., (m + b) / 2.);
+    let (flm, frm) = (f(lm)?, f(rm)?);
+    *budget = budget.saturating_sub(2);
+    let left = simpson(a, m, fa, flm, fm);
+    let right = simpson(m, b, fm, frm, fb);
+    let delta = left + right - whole;
+    // the tolerance cannot go below what the floating point sum can resolve
+    let tolerance = tolerance.max(f64::EPSILON * (left + right).abs());
+    if depth == 0 || *budget == 0 || delta.abs() <= 15. * tolerance || !delta.is_finite() {
+        return Ok(left + right + delta / 15.)
+    }
+    let left = adaptive(f, a, m, fa, flm, fm, left, tolerance / 2., depth - 1, budget)?;
+    Ok(left + adaptive(f, m, b, fm, frm, fb, right, tolerance / 2., depth - 1, budget)?)
+}
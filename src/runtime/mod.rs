@@ -1,2 +1,11 @@
 pub mod value;
-pub mod eval;
\ No newline at end of file
+pub mod eval;
+pub mod rules;
+pub mod scope;
+pub mod builtins;
+pub mod solve;
+pub mod diff;
+pub mod integrate;
+pub mod sum;
+pub mod units;
+pub mod format;
\ No newline at end of file
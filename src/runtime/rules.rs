@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use num_traits::ToPrimitive;
+
+use crate::*;
+use runtime::builtins;
+use runtime::eval::{self, EvalResult, Return};
+use runtime::scope::Scope;
+use runtime::value::Value;
+use scanning::expr::{Expr, ExprKind};
+use scanning::token::Token;
+
+pub const RULE_SHEET: &str = include_str!("../rule_sheet.vac");
+/// upper bound on rewrites per simplification, guards against rules that undo each other
+pub const MAX_REWRITES: usize = 1000;
+/// marks a pattern variable that only matches constants
+pub const CONSTANT: &str = "const";
+
+/// a `pattern -> replacement` line of a rule sheet,
+/// identifiers in the pattern match any expression and are substituted in the replacement,
+/// `const(a)` only matches constants, expressions without identifiers, and named constants like `pi` only match themselves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub pattern: Expr,
+    pub replacement: Expr,
+}
+impl Rule {
+    pub fn parse(line: &str) -> Result<Self, Error> {
+        let tokens = scanning::lexer::lex(line.to_string())?;
+        let Some(idx) = tokens.iter().position(|(token, _)| token == &Token::Into) else {
+            return error!(Parse, "expected token '{}' in rule", Token::Into)
+        };
+        let pattern = scanning::parser::parse(tokens[..idx].to_vec())?;
+        let replacement = scanning::parser::parse(tokens[idx + 1..].to_vec())?;
+        Ok(Self { pattern, replacement })
+    }
+    pub fn apply(&self, expr: &Expr) -> Option<Expr> {
+        let mut bindings = HashMap::new();
+        if matches(&self.pattern, expr, &mut bindings) {
+            Some(substitute(&self.replacement, &bindings))
+        } else {
+            None
+        }
+    }
+}
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {} {}", self.pattern, Token::Into, self.replacement)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+impl RuleSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut rules = vec![];
+        for (ln, line) in source.lines().enumerate() {
+            // lines starting with `#` are comments
+            if line.trim().is_empty() || line.trim_start().starts_with('#') { continue }
+            match Rule::parse(line) {
+                Ok(rule) => rules.push(rule),
+                Err(err) => return Err(Error { message: format!("rule {}: {err}", ln + 1), ..err })
+            }
+        }
+        Ok(Self { rules })
+    }
+    /// rewrites the first matching sub-expression, outermost first
+    pub fn rewrite(&self, expr: &Expr) -> Option<Expr> {
+        for rule in self.rules.iter() {
+            if let Some(new) = rule.apply(expr) { return Some(new) }
+        }
+        let kind = match &expr.kind {
+            ExprKind::BinaryOperation { left, right, op } => {
+                if let Some(left) = self.rewrite(left) {
+                    return Some(Expr::binary(left, right.as_ref().clone(), op.clone()))
+                }
+                let right = self.rewrite(right)?;
+                return Some(Expr::binary(left.as_ref().clone(), right, op.clone()))
+            }
+            ExprKind::UnaryOperation { expr, op } => ExprKind::UnaryOperation { expr: Box::new(self.rewrite(expr)?), op: op.clone() },
+            ExprKind::UnaryOperationRight { expr, op } => ExprKind::UnaryOperationRight { expr: Box::new(self.rewrite(expr)?), op: op.clone() },
+            ExprKind::Absolute(expr) => ExprKind::Absolute(Box::new(self.rewrite(expr)?)),
+            ExprKind::Vector(exprs) => ExprKind::Vector(self.rewrite_all(exprs)?),
+            ExprKind::Set(exprs) => ExprKind::Set(self.rewrite_all(exprs)?),
+            ExprKind::Call { id, args } => ExprKind::Call { id: id.clone(), args: self.rewrite_all(args)? },
+            ExprKind::ID(_) | ExprKind::Int(_) | ExprKind::Float(_) | ExprKind::Continue | ExprKind::Unit(_) | ExprKind::Store { .. } | ExprKind::Error => return None,
+        };
+        Some(Expr::new(kind, expr.span))
+    }
+    fn rewrite_all(&self, exprs: &[Expr]) -> Option<Vec<Expr>> {
+        for (idx, expr) in exprs.iter().enumerate() {
+            if let Some(new) = self.rewrite(expr) {
+                let mut exprs = exprs.to_vec();
+                exprs[idx] = new;
+                return Some(exprs)
+            }
+        }
+        None
+    }
+    /// rewrites and re-evaluates a symbolic result until no rule applies anymore
+    pub fn simplify(&self, ret: Return, scope: &mut Scope) -> EvalResult {
+        let mut ret = ret;
+        for _ in 0..MAX_REWRITES {
+            let Return::Expr(expr) = &ret else { break };
+            let Some(new) = self.rewrite(expr) else { break };
+            ret = eval::eval(&new, scope)?;
+        }
+        Ok(ret)
+    }
+}
+
+fn number(expr: &Expr) -> Option<f64> {
+    match &expr.kind {
+        ExprKind::Int(v) => v.to_f64(),
+        ExprKind::Float(v) => Some(*v),
+        _ => None
+    }
+}
+
+pub fn matches(pattern: &Expr, expr: &Expr, bindings: &mut HashMap<String, Expr>) -> bool {
+    match (&pattern.kind, &expr.kind) {
+        (ExprKind::Call { id, args }, _) if id == CONSTANT => match args.as_slice() {
+            [pattern] if expr.ids().is_empty() => matches(pattern, expr, bindings),
+            _ => false
+        }
+        (ExprKind::ID(id), _) if builtins::constant(id).is_some() => pattern == expr,
+        (ExprKind::ID(id), _) => match bindings.get(id) {
+            Some(bound) => bound == expr,
+            None => {
+                bindings.insert(id.clone(), expr.clone());
+                true
+            }
+        }
+        (ExprKind::Int(_) | ExprKind::Float(_), _) => number(pattern).is_some() && number(pattern) == number(expr),
+        (ExprKind::UnaryOperation { expr: pattern, op: Token::Sub }, ExprKind::Int(_) | ExprKind::Float(_)) => match number(expr) {
+            // evaluated negative numbers are literals, `-b` still has to match them
+            Some(v) if v < 0. => matches(pattern, &Value::Number(-v).expr(), bindings),
+            _ => false
+        }
+        (ExprKind::BinaryOperation { left: pattern_left, right: pattern_right, op: pattern_op }, ExprKind::BinaryOperation { left, right, op }) =>
+            pattern_op == op && matches(pattern_left, left, bindings) && matches(pattern_right, right, bindings),
+        (ExprKind::UnaryOperation { expr: pattern, op: pattern_op }, ExprKind::UnaryOperation { expr, op }) |
+        (ExprKind::UnaryOperationRight { expr: pattern, op: pattern_op }, ExprKind::UnaryOperationRight { expr, op }) =>
+            pattern_op == op && matches(pattern, expr, bindings),
+        (ExprKind::Absolute(pattern), ExprKind::Absolute(expr)) => matches(pattern, expr, bindings),
+        (ExprKind::Vector(patterns), ExprKind::Vector(exprs)) | (ExprKind::Set(patterns), ExprKind::Set(exprs)) =>
+            patterns.len() == exprs.len() && patterns.iter().zip(exprs.iter()).all(|(pattern, expr)| matches(pattern, expr, bindings)),
+        (ExprKind::Call { id: pattern_id, args: patterns }, ExprKind::Call { id, args: exprs }) =>
+            pattern_id == id && patterns.len() == exprs.len() && patterns.iter().zip(exprs.iter()).all(|(pattern, expr)| matches(pattern, expr, bindings)),
+        _ => pattern == expr
+    }
+}
+
+pub fn substitute(expr: &Expr, bindings: &HashMap<String, Expr>) -> Expr {
+    let kind = match &expr.kind {
+        ExprKind::ID(id) => return bindings.get(id).cloned().unwrap_or_else(|| expr.clone()),
+        ExprKind::Int(_) | ExprKind::Float(_) | ExprKind::Continue | ExprKind::Unit(_) | ExprKind::Error => return expr.clone(),
+        ExprKind::BinaryOperation { left, right, op } => ExprKind::BinaryOperation {
+            left: Box::new(substitute(left, bindings)), right: Box::new(substitute(right, bindings)), op: op.clone()
+        },
+        ExprKind::UnaryOperation { expr, op } => ExprKind::UnaryOperation { expr: Box::new(substitute(expr, bindings)), op: op.clone() },
+        ExprKind::UnaryOperationRight { expr, op } => ExprKind::UnaryOperationRight { expr: Box::new(substitute(expr, bindings)), op: op.clone() },
+        ExprKind::Absolute(expr) => ExprKind::Absolute(Box::new(substitute(expr, bindings))),
+        ExprKind::Vector(exprs) => ExprKind::Vector(exprs.iter().map(|expr| substitute(expr, bindings)).collect()),
+        ExprKind::Set(exprs) => ExprKind::Set(exprs.iter().map(|expr| substitute(expr, bindings)).collect()),
+        ExprKind::Call { id, args } => ExprKind::Call { id: id.clone(), args: args.iter().map(|expr| substitute(expr, bindings)).collect() },
+        ExprKind::Store { target, expr } => ExprKind::Store { target: Box::new(substitute(target, bindings)), expr: Box::new(substitute(expr, bindings)) },
+    };
+    Expr::from(kind)
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::*;
+use runtime::eval::Return;
+use runtime::format::Format;
+use runtime::rules::RuleSet;
+use runtime::value::Value;
+use scanning::expr::Expr;
+
+/// without conditionals any recursion is a cycle, so this only has to stay below the stack of a thread
+pub const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Function {
+    pub params: Vec<String>,
+    pub body: Expr,
+}
+
+/// a function implemented by the program embedding vac, called with evaluated arguments
+pub type NativeFn = dyn Fn(&[Value]) -> Result<Value, Error> + Send + Sync;
+#[derive(Clone)]
+pub struct Native(pub Arc<NativeFn>);
+impl std::fmt::Debug for Native {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Native")
+    }
+}
+impl PartialEq for Native {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// variable bindings, the innermost frame is searched first
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scope {
+    frames: Vec<HashMap<String, Return>>,
+    functions: HashMap<String, Function>,
+    natives: HashMap<String, Native>,
+    /// keeps constants like `pi` symbolic until `num` is called
+    pub symbolic: bool,
+    pub rules: Arc<RuleSet>,
+    pub format: Format,
+}
+impl Scope {
+    pub fn new() -> Self {
+        Self { frames: vec![HashMap::new()], functions: HashMap::new(), natives: HashMap::new(), symbolic: false, rules: Arc::new(RuleSet::new()), format: Format::default() }
+    }
+    pub fn push(&mut self) -> Result<(), Error> {
+        if self.frames.len() >= MAX_DEPTH { return error!(Limit, "maximum call depth of {MAX_DEPTH} exceeded") }
+        self.frames.push(HashMap::new());
+        Ok(())
+    }
+    pub fn pop(&mut self) {
+        if self.frames.len() > 1 { self.frames.pop(); }
+    }
+    pub fn get(&self, id: &str) -> Option<&Return> {
+        self.frames.iter().rev().find_map(|frame| frame.get(id))
+    }
+    pub fn set(&mut self, id: String, ret: Return) {
+        if let Some(frame) = self.frames.last_mut() { frame.insert(id, ret); }
+    }
+    pub fn get_function(&self, id: &str) -> Option<&Function> {
+        self.functions.get(id)
+    }
+    pub fn set_function(&mut self, id: String, function: Function) {
+        self.functions.insert(id, function);
+    }
+    pub fn get_native(&self, id: &str) -> Option<&Native> {
+        self.natives.get(id)
+    }
+    pub fn set_native(&mut self, id: String, native: Native) {
+        self.natives.insert(id, native);
+    }
+}
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
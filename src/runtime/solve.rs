@@ -0,0 +1,229 @@
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use crate::*;
+use runtime::builtins;
+use runtime::eval::{self, EvalResult, Return};
+use runtime::scope::Scope;
+use runtime::value::Value;
+use scanning::expr::{Expr, ExprKind};
+use scanning::token::Token;
+
+/// numeric roots are searched for in growing ranges until one of them has any
+pub const SEARCH_RANGES: [f64; 3] = [10., 100., 1000.];
+pub const SEARCH_STEPS: usize = 2000;
+const TOLERANCE: f64 = 1e-9;
+
+/// `solve(equation, unknown)`, the unknown can be left out if the equation only has one
+pub fn solve(args: &[Expr], scope: &mut Scope) -> EvalResult {
+    let (equation, unknown) = match args {
+        [equation, Expr { kind: ExprKind::ID(unknown), .. }] => (equation, Some(unknown)),
+        [equation] => (equation, None),
+        [_, arg] => return error!(TypeMismatch, "expected identifier as unknown, got {} `{arg}`", arg.name()),
+        _ => return error!(Arity, "expected 1 or 2 arguments for `solve`, got {}", args.len())
+    };
+    let ret = match unknown {
+        Some(unknown) => eval::eval_symbolic(equation, unknown, scope)?,
+        None => eval::eval(equation, scope)?
+    };
+    let expr = match ret {
+        Return::Expr(expr) => expr,
+        Return::Value(Value::Bool(bool)) => return Ok(Return::Value(Value::Bool(bool))),
+        Return::Value(value) => value.expr(),
+        Return::None => return error!(TypeMismatch, "expected an equation, got nothing")
+    };
+    let unknown = match unknown {
+        Some(unknown) => unknown.clone(),
+        None => match unknowns(&expr).as_slice() {
+            [unknown] => unknown.clone(),
+            [] => return error!(UnboundName, "expected an unknown in `{expr}`"),
+            unknowns => return error!(UnboundName, "expected a single unknown in `{expr}`, got {}", unknowns.join(", "))
+        }
+    };
+    roots(&function(expr), &unknown, scope)
+}
+
+/// solves a symbolic equation with exactly one unknown, anything else is returned as is
+pub fn equation(ret: Return, scope: &mut Scope) -> EvalResult {
+    let Return::Expr(expr @ Expr { kind: ExprKind::BinaryOperation { op: Token::Equal, .. }, .. }) = &ret else { return Ok(ret) };
+    let unknowns = unknowns(expr);
+    let [unknown] = unknowns.as_slice() else { return Ok(ret) };
+    roots(&function(expr.clone()), unknown, scope)
+}
+
+fn unknowns(expr: &Expr) -> Vec<String> {
+    expr.ids().into_iter().filter(|id| builtins::constant(id).is_none()).collect()
+}
+
+/// `left = right` as `left - right`, which is zero at every solution
+fn function(expr: Expr) -> Expr {
+    match expr.kind {
+        ExprKind::BinaryOperation { left, right, op: Token::Equal } => Expr::binary(*left, *right, Token::Sub),
+        _ => expr
+    }
+}
+
+pub fn roots(f: &Expr, x: &str, scope: &mut Scope) -> EvalResult {
+    let Some(coefficients) = polynomial(f, x) else {
+        return Ok(Return::Value(Value::set(numeric_roots(f, x, scope)?.into_iter().map(Value::Number).collect())))
+    };
+    let mut rets = vec![];
+    for coefficient in coefficients.iter() {
+        let ret = eval::eval(coefficient, scope)?;
+        rets.push(eval::simplify(ret, scope)?);
+    }
+    while matches!(rets.last(), Some(Return::Value(value)) if value.number() == Some(0.)) { rets.pop(); }
+    let numbers = rets.iter().map(|ret| match ret {
+        Return::Value(value) if value.number().is_some() => Some(value.clone()),
+        _ => None
+    }).collect::<Option<Vec<Value>>>();
+    let roots = match numbers.as_deref() {
+        Some([]) => return Ok(Return::Value(Value::Bool(true))),
+        Some([_]) => vec![],
+        Some([c, b]) => vec![c.unary(&Token::Sub)?.binary(b, &Token::Div)?],
+        // exact as long as the coefficients and the root of the discriminant are rational, complex if it is negative
+        Some([c, b, a]) => {
+            let discriminant = b.binary(b, &Token::Mult)?.binary(&Value::integer(4).binary(a, &Token::Mult)?.binary(c, &Token::Mult)?, &Token::Sub)?;
+            let (root, minus_b, a2) = (discriminant.sqrt()?, b.unary(&Token::Sub)?, Value::integer(2).binary(a, &Token::Mult)?);
+            vec![minus_b.binary(&root, &Token::Add)?.binary(&a2, &Token::Div)?, minus_b.binary(&root, &Token::Sub)?.binary(&a2, &Token::Div)?]
+        }
+        Some(_) => numeric_roots(f, x, scope)?.into_iter().map(Value::Number).collect(),
+        None => {
+            let coefficients = rets.iter().map(Return::expr).collect::<Vec<Expr>>();
+            let roots = match coefficients.as_slice() {
+                [c, b] => vec![Expr::binary(neg(c.clone()), b.clone(), Token::Div)],
+                [c, b, a] => {
+                    let root = Expr::call("sqrt", vec![Expr::binary(
+                        Expr::binary(b.clone(), Expr::int(2), Token::Power),
+                        Expr::binary(Expr::binary(Expr::int(4), a.clone(), Token::Mult), c.clone(), Token::Mult),
+                        Token::Sub
+                    )]);
+                    let a2 = Expr::binary(Expr::int(2), a.clone(), Token::Mult);
+                    vec![
+                        Expr::binary(Expr::binary(neg(b.clone()), root.clone(), Token::Sub), a2.clone(), Token::Div),
+                        Expr::binary(Expr::binary(neg(b.clone()), root, Token::Add), a2, Token::Div),
+                    ]
+                }
+                _ => return error!(Unsupported, "cannot solve `{f}` for {x}")
+            };
+            // every root on its own, so the rules simplify each branch
+            let mut set = vec![];
+            for root in roots.iter() {
+                let ret = eval::eval(root, scope)?;
+                set.push(eval::simplify(ret, scope)?);
+            }
+            return match set.iter().map(|ret| ret.value().cloned()).collect::<Option<Vec<Value>>>() {
+                Some(values) => Ok(Return::Value(Value::set(values))),
+                None => Ok(Return::Expr(Expr::from(ExprKind::Set(set.iter().map(Return::expr).collect()))))
+            }
+        }
+    };
+    Ok(Return::Value(Value::set(roots)))
+}
+
+fn neg(expr: Expr) -> Expr {
+    Expr::unary(expr, Token::Sub)
+}
+
+/// coefficients of `expr` as a polynomial in `x`, lowest power first, none of them contain `x`
+pub fn polynomial(expr: &Expr, x: &str) -> Option<Vec<Expr>> {
+    if !expr.ids().iter().any(|id| id == x) { return Some(vec![expr.clone()]) }
+    match &expr.kind {
+        ExprKind::ID(_) => Some(vec![Expr::int(0), Expr::int(1)]),
+        ExprKind::UnaryOperation { expr, op: Token::Sub } => Some(polynomial(expr, x)?.into_iter().map(neg).collect()),
+        ExprKind::BinaryOperation { left, right, op } => match op {
+            Token::Add => Some(add(polynomial(left, x)?, polynomial(right, x)?)),
+            Token::Sub => Some(add(polynomial(left, x)?, polynomial(right, x)?.into_iter().map(neg).collect())),
+            Token::Mult => Some(mult(&polynomial(left, x)?, &polynomial(right, x)?)),
+            Token::Div if !right.ids().iter().any(|id| id == x) =>
+                Some(polynomial(left, x)?.into_iter().map(|c| Expr::binary(c, right.as_ref().clone(), Token::Div)).collect()),
+            Token::Power => match &right.kind {
+                ExprKind::Int(exponent) if exponent <= &BigInt::from(16) && exponent >= &BigInt::from(0) => {
+                    let left = polynomial(left, x)?;
+                    let mut acc = vec![Expr::int(1)];
+                    for _ in 0..exponent.to_u32().unwrap_or(0) { acc = mult(&acc, &left); }
+                    Some(acc)
+                }
+                _ => None
+            }
+            _ => None
+        }
+        _ => None
+    }
+}
+fn add(left: Vec<Expr>, right: Vec<Expr>) -> Vec<Expr> {
+    let (mut left, mut right) = (left.into_iter(), right.into_iter());
+    let mut coefficients = vec![];
+    loop {
+        match (left.next(), right.next()) {
+            (Some(a), Some(b)) => coefficients.push(Expr::binary(a, b, Token::Add)),
+            (Some(c), None) | (None, Some(c)) => coefficients.push(c),
+            (None, None) => break
+        }
+    }
+    coefficients
+}
+fn mult(left: &[Expr], right: &[Expr]) -> Vec<Expr> {
+    let mut coefficients: Vec<Option<Expr>> = vec![None; left.len() + right.len() - 1];
+    for (i, a) in left.iter().enumerate() {
+        for (j, b) in right.iter().enumerate() {
+            let product = Expr::binary(a.clone(), b.clone(), Token::Mult);
+            coefficients[i + j] = Some(match coefficients[i + j].take() {
+                Some(sum) => Expr::binary(sum, product, Token::Add),
+                None => product
+            });
+        }
+    }
+    coefficients.into_iter().map(|c| c.unwrap_or(Expr::int(0))).collect()
+}
+
+/// `f` at `x = v`, evaluation errors count as undefined
+fn at(f: &Expr, x: &str, v: f64, scope: &mut Scope) -> Result<f64, Error> {
+    match eval::eval_at(f, x, v, scope) {
+        Ok(Return::Value(value)) if value.number().is_some() => Ok(value.number().unwrap_or(f64::NAN)),
+        Ok(Return::Value(Value::Complex(_))) => Ok(f64::NAN),
+        Ok(Return::Expr(expr)) => error!(UnboundName, "cannot solve for {x}, `{expr}` has other unknowns"),
+        Ok(ret) => error!(TypeMismatch, "cannot solve for {x}, expected a number, got {ret}"),
+        Err(_) => Ok(f64::NAN)
+    }
+}
+
+/// scans for sign changes and refines them by bisection, poles are rejected
+pub fn numeric_roots(f: &Expr, x: &str, scope: &mut Scope) -> Result<Vec<f64>, Error> {
+    for range in SEARCH_RANGES {
+        let roots = scan(f, x, range, scope)?;
+        if !roots.is_empty() { return Ok(roots) }
+    }
+    Ok(vec![])
+}
+fn scan(f: &Expr, x: &str, range: f64, scope: &mut Scope) -> Result<Vec<f64>, Error> {
+    let mut roots: Vec<f64> = vec![];
+    let step = 2. * range / SEARCH_STEPS as f64;
+    let mut a = -range;
+    let mut fa = at(f, x, a, scope)?;
+    for i in 1..=SEARCH_STEPS {
+        let b = -range + i as f64 * step;
+        let fb = at(f, x, b, scope)?;
+        let root = if fb == 0. {
+            Some(b)
+        } else if fa.is_finite() && fb.is_finite() && fa != 0. && fa.signum() != fb.signum() {
+            let (mut low, mut high, mut f_low) = (a, b, fa);
+            for _ in 0..100 {
+                let mid = (low + high) / 2.;
+                let f_mid = at(f, x, mid, scope)?;
+                if f_mid == 0. { (low, high) = (mid, mid); break }
+                if f_mid.signum() == f_low.signum() { (low, f_low) = (mid, f_mid) } else { high = mid }
+            }
+            let root = (low + high) / 2.;
+            if at(f, x, root, scope)?.abs() < TOLERANCE.sqrt() { Some(root) } else { None }
+        } else {
+            None
+        };
+        if let Some(mut root) = root {
+            if (root - root.round()).abs() < TOLERANCE { root = root.round() }
+            if !roots.iter().any(|r| (r - root).abs() < TOLERANCE) { roots.push(root) }
+        }
+        (a, fa) = (b, fb);
+    }
+    Ok(roots)
+}
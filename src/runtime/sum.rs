@@ -0,0 +1,39 @@
+use crate::*;
+use runtime::eval::{self, EvalResult, Return};
+use runtime::scope::Scope;
+use runtime::value::{Value, MAX_RANGE};
+use scanning::expr::{Expr, ExprKind};
+use scanning::token::Token;
+
+/// `sum(expr, k, a, b)` and `prod(expr, k, a, b)` with `op` being `+` or `*`,
+/// the bounds can also be given as a single range like `a ... b` or any vector or set
+pub fn sum(args: &[Expr], op: Token, scope: &mut Scope) -> EvalResult {
+    let id = if op == Token::Mult { "prod" } else { "sum" };
+    let (expr, k, range) = match args {
+        [expr, Expr { kind: ExprKind::ID(k), .. }, a, b] => (expr, k, Expr::binary(a.clone(), b.clone(), Token::Continue)),
+        [expr, Expr { kind: ExprKind::ID(k), .. }, range] => (expr, k, range.clone()),
+        [_, arg, ..] => return error!(TypeMismatch, "expected identifier as index of `{id}`, got {} `{arg}`", arg.name()),
+        _ => return error!(Arity, "expected 3 or 4 arguments for `{id}`, got {}", args.len())
+    };
+    let indices = match eval::eval(&range, scope)? {
+        Return::Value(Value::Vector(values, _)) => values,
+        Return::Value(Value::Set(values, _)) => values.into_iter().collect(),
+        Return::Value(value) => vec![value],
+        Return::Expr(range) => return Ok(Return::Expr(Expr::call(id, vec![expr.clone(), Expr::id(k), range]))),
+        Return::None => return error!(TypeMismatch, "expected a range for `{id}`, got nothing")
+    };
+    if indices.len() > MAX_RANGE { return error!(Limit, "too many terms for `{id}`") }
+    let mut acc = Return::Value(Value::integer(if op == Token::Mult { 1 } else { 0 }));
+    for index in indices {
+        scope.push()?;
+        scope.set(k.clone(), Return::Value(index));
+        let ret = eval::eval(expr, scope);
+        scope.pop();
+        acc = match (acc, ret?) {
+            (Return::Value(acc), Return::Value(value)) => Return::Value(acc.binary(&value, &op)?),
+            (acc, Return::None) | (Return::None, acc) => return error!(TypeMismatch, "expected a value, got {acc}"),
+            (acc, ret) => eval::eval(&Expr::binary(acc.expr(), ret.expr(), op.clone()), scope)?
+        };
+    }
+    eval::simplify(acc, scope)
+}
@@ -0,0 +1,173 @@
+use std::fmt::Display;
+
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::One;
+
+use crate::*;
+
+/// the SI base units, every dimension is a power of each of them
+pub const BASE: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+pub const PREFIXES: [(&str, i32); 25] = [
+    ("da", 1), ("Q", 30), ("R", 27), ("Y", 24), ("Z", 21), ("E", 18), ("P", 15), ("T", 12), ("G", 9), ("M", 6), ("k", 3), ("h", 2), ("d", -1),
+    ("c", -2), ("m", -3), ("u", -6), ("µ", -6), ("n", -9), ("p", -12), ("f", -15), ("a", -18), ("z", -21), ("y", -24), ("r", -27), ("q", -30),
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dimension(pub [i32; 7]);
+impl Dimension {
+    pub fn is_none(&self) -> bool {
+        self.0.iter().all(|exponent| *exponent == 0)
+    }
+    pub fn mul(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] + other.0[i]))
+    }
+    pub fn div(&self, other: &Self) -> Self {
+        Self(std::array::from_fn(|i| self.0[i] - other.0[i]))
+    }
+    pub fn pow(&self, n: i32) -> Self {
+        Self(self.0.map(|exponent| exponent * n))
+    }
+    /// the `n`th root, if every exponent is divisible by `n`
+    pub fn root(&self, n: i32) -> Option<Self> {
+        if self.0.iter().any(|exponent| exponent % n != 0) { return None }
+        Some(Self(self.0.map(|exponent| exponent / n)))
+    }
+}
+impl Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let atom = |(symbol, exponent): (&str, i32)| if exponent == 1 { symbol.to_string() } else { format!("{symbol}^{exponent}") };
+        let numerator = BASE.iter().zip(self.0).filter(|(_, exponent)| *exponent > 0)
+            .map(|(symbol, exponent)| atom((symbol, exponent))).collect::<Vec<String>>();
+        let denominator = BASE.iter().zip(self.0).filter(|(_, exponent)| *exponent < 0)
+            .map(|(symbol, exponent)| atom((symbol, -exponent))).collect::<Vec<String>>();
+        match (numerator.is_empty(), denominator.is_empty()) {
+            (true, true) => write!(f, "1"),
+            (false, true) => write!(f, "{}", numerator.join(" ")),
+            (true, false) => write!(f, "1/{}", denominator.join(" ")),
+            (false, false) => write!(f, "{}/{}", numerator.join(" "), denominator.join(" ")),
+        }
+    }
+}
+
+/// a quantity of `1 name` is `scale` in SI base units
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    pub name: String,
+    pub scale: BigRational,
+    pub dimension: Dimension,
+}
+impl Unit {
+    pub fn none() -> Self {
+        Self { name: String::new(), scale: BigRational::one(), dimension: Dimension::default() }
+    }
+    /// the coherent SI unit of a dimension, named after the base units
+    pub fn si(dimension: Dimension) -> Self {
+        Self { name: dimension.to_string(), scale: BigRational::one(), dimension }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    Unknown(String),
+    Incompatible { left: Dimension, right: Dimension },
+    Exponent(String),
+    Argument { id: String, value: String },
+}
+impl Display for UnitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(f, "unknown unit `{name}`"),
+            Self::Incompatible { left, right } => write!(f, "incompatible units {left} and {right}"),
+            Self::Exponent(value) => write!(f, "expected a dimensionless integer as exponent of a quantity, got {value}"),
+            Self::Argument { id, value } => write!(f, "expected a dimensionless number for `{id}`, got {value}"),
+        }
+    }
+}
+impl std::error::Error for UnitError {}
+impl From<UnitError> for Error {
+    fn from(error: UnitError) -> Self {
+        Error::new(ErrorKind::Unit, error.to_string())
+    }
+}
+
+fn ratio(numerator: i64, exponent: u32) -> BigRational {
+    BigRational::new(BigInt::from(numerator), BigInt::from(10).pow(exponent))
+}
+
+/// scale, dimension and whether SI prefixes apply
+fn base(symbol: &str) -> Option<(BigRational, [i32; 7], bool)> {
+    let one = BigRational::one();
+    Some(match symbol {
+        "m" => (one, [1, 0, 0, 0, 0, 0, 0], true),
+        "g" => (ratio(1, 3), [0, 1, 0, 0, 0, 0, 0], true),
+        "s" => (one, [0, 0, 1, 0, 0, 0, 0], true),
+        "A" => (one, [0, 0, 0, 1, 0, 0, 0], true),
+        "K" => (one, [0, 0, 0, 0, 1, 0, 0], true),
+        "mol" => (one, [0, 0, 0, 0, 0, 1, 0], true),
+        "cd" => (one, [0, 0, 0, 0, 0, 0, 1], true),
+        "Hz" => (one, [0, 0, -1, 0, 0, 0, 0], true),
+        "N" => (one, [1, 1, -2, 0, 0, 0, 0], true),
+        "Pa" => (one, [-1, 1, -2, 0, 0, 0, 0], true),
+        "J" => (one, [2, 1, -2, 0, 0, 0, 0], true),
+        "W" => (one, [2, 1, -3, 0, 0, 0, 0], true),
+        "C" => (one, [0, 0, 1, 1, 0, 0, 0], true),
+        "V" => (one, [2, 1, -3, -1, 0, 0, 0], true),
+        "ohm" | "Ω" => (one, [2, 1, -3, -2, 0, 0, 0], true),
+        "S" => (one, [-2, -1, 3, 2, 0, 0, 0], true),
+        "F" => (one, [-2, -1, 4, 2, 0, 0, 0], true),
+        "H" => (one, [2, 1, -2, -2, 0, 0, 0], true),
+        "T" => (one, [0, 1, -2, -1, 0, 0, 0], true),
+        "Wb" => (one, [2, 1, -2, -1, 0, 0, 0], true),
+        "L" => (ratio(1, 3), [3, 0, 0, 0, 0, 0, 0], true),
+        "eV" => (ratio(1_602_176_634, 28), [2, 1, -2, 0, 0, 0, 0], true),
+        "Wh" => (ratio(3600, 0), [2, 1, -2, 0, 0, 0, 0], true),
+        "cal" => (ratio(4184, 3), [2, 1, -2, 0, 0, 0, 0], true),
+        "bar" => (ratio(100_000, 0), [-1, 1, -2, 0, 0, 0, 0], true),
+        "atm" => (ratio(101_325, 0), [-1, 1, -2, 0, 0, 0, 0], false),
+        "min" => (ratio(60, 0), [0, 0, 1, 0, 0, 0, 0], false),
+        "h" => (ratio(3600, 0), [0, 0, 1, 0, 0, 0, 0], false),
+        "d" => (ratio(86_400, 0), [0, 0, 1, 0, 0, 0, 0], false),
+        "in" => (ratio(254, 4), [1, 0, 0, 0, 0, 0, 0], false),
+        "ft" => (ratio(3048, 4), [1, 0, 0, 0, 0, 0, 0], false),
+        "yd" => (ratio(9144, 4), [1, 0, 0, 0, 0, 0, 0], false),
+        "mi" => (ratio(1_609_344, 3), [1, 0, 0, 0, 0, 0, 0], false),
+        "lb" => (ratio(45_359_237, 8), [0, 1, 0, 0, 0, 0, 0], false),
+        "oz" => (ratio(45_359_237, 8) / BigInt::from(16), [0, 1, 0, 0, 0, 0, 0], false),
+        "mph" => (ratio(1_609_344, 3) / BigInt::from(3600), [1, 0, -1, 0, 0, 0, 0], false),
+        "kn" => (ratio(1852, 0) / BigInt::from(3600), [1, 0, -1, 0, 0, 0, 0], false),
+        _ => return None
+    })
+}
+
+/// a single unit symbol, optionally with an SI prefix
+pub fn get(symbol: &str) -> Option<Unit> {
+    let unit = |scale, dimension| Unit { name: symbol.to_string(), scale, dimension: Dimension(dimension) };
+    if let Some((scale, dimension, _)) = base(symbol) { return Some(unit(scale, dimension)) }
+    for (prefix, exponent) in PREFIXES {
+        let Some((scale, dimension, true)) = symbol.strip_prefix(prefix).and_then(base) else { continue };
+        let factor = BigRational::from_integer(BigInt::from(10)).pow(exponent);
+        return Some(unit(scale * factor, dimension))
+    }
+    None
+}
+
+/// a product of unit symbols with integer powers, everything after `/` is divided by,
+/// like `kg m^2/s^2` or `1/s`
+pub fn parse(name: &str) -> Result<Unit, UnitError> {
+    let (numerator, denominator) = name.split_once('/').unwrap_or((name, ""));
+    let mut unit = Unit::none();
+    for (part, sign) in [(numerator, 1), (denominator, -1)] {
+        for atom in part.split_whitespace().filter(|atom| *atom != "1") {
+            let (symbol, exponent) = match atom.split_once('^') {
+                Some((symbol, exponent)) => (symbol, exponent.parse::<i32>().map_err(|_| UnitError::Unknown(atom.to_string()))?),
+                None => (atom, 1)
+            };
+            let base = get(symbol).ok_or_else(|| UnitError::Unknown(symbol.to_string()))?;
+            unit.scale *= base.scale.pow(sign * exponent);
+            unit.dimension = unit.dimension.mul(&base.dimension.pow(sign * exponent));
+        }
+    }
+    unit.name = name.to_string();
+    Ok(unit)
+}
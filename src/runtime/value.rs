@@ -1,40 +1,208 @@
 use std::{collections::HashSet, hash::Hash, fmt::Display};
-use crate::{*, scanning::expr::Expr};
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_rational::BigRational;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+use crate::{*, scanning::expr::{Expr, ExprKind}};
+use runtime::builtins;
+use runtime::format::Format;
+use runtime::units::{Dimension, Unit, UnitError};
 use scanning::token::Token;
 
+pub const MAX_RANGE: usize = 10_000_000;
+/// rational powers with a larger exponent fall back to floating point
+pub const MAX_EXPONENT: i32 = 100_000;
+/// exact factorials above this are too slow to be useful
+pub const MAX_FACTORIAL: u64 = 100_000;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
-    Number(f64), Vector(Vec<Value>, Type), Set(HashSet<Value>, Type)
+    Number(f64), Integer(BigInt), Rational(BigRational), Complex(Complex64), Quantity(Box<Value>, Unit), Radix(BigInt, u32), Bool(bool), Vector(Vec<Value>, Type), Set(HashSet<Value>, Type)
 }
 impl Value {
+    pub fn integer(number: i64) -> Self {
+        Self::Integer(BigInt::from(number))
+    }
+    /// an exact value, integers are kept apart from fractions
+    pub fn exact(rational: BigRational) -> Self {
+        if rational.is_integer() { Self::Integer(rational.to_integer()) } else { Self::Rational(rational) }
+    }
+    /// a complex value, it is real if the imaginary part is zero
+    pub fn complex(complex: Complex64) -> Self {
+        if complex.im == 0. { Self::Number(complex.re) } else { Self::Complex(complex) }
+    }
+    pub fn as_complex(&self) -> Option<Complex64> {
+        match self {
+            Self::Complex(complex) => Some(*complex),
+            value => value.number().map(|number| Complex64::new(number, 0.))
+        }
+    }
+    /// a number with a unit, it is a plain number if the unit is dimensionless
+    pub fn quantity(magnitude: Value, unit: Unit) -> Self {
+        if unit.dimension.is_none() && unit.scale.is_one() { magnitude } else { Self::Quantity(Box::new(magnitude), unit) }
+    }
+    /// the magnitude and unit, plain numbers have no unit
+    pub fn parts(&self) -> (Value, Unit) {
+        match self {
+            Self::Quantity(magnitude, unit) => (magnitude.as_ref().clone(), unit.clone()),
+            value => (value.clone(), Unit::none())
+        }
+    }
+    /// the quantity in `unit`, which has to have the same dimension
+    pub fn convert(&self, unit: &Unit) -> Result<Self, Error> {
+        self.each(&|value| {
+            let (magnitude, from) = value.parts();
+            if from.dimension != unit.dimension {
+                return Err(UnitError::Incompatible { left: from.dimension, right: unit.dimension }.into())
+            }
+            let magnitude = magnitude.binary(&Value::exact(from.scale / &unit.scale), &Token::Mult)?;
+            // conversions are approximate by nature, fractions would only be noise
+            let magnitude = if matches!(magnitude, Value::Rational(_)) { magnitude.float() } else { magnitude };
+            Ok(Value::Quantity(Box::new(magnitude), unit.clone()))
+        })
+    }
+    /// the integer printed in base `radix`, it computes like any other integer
+    pub fn radix(&self, radix: u32) -> Result<Self, Error> {
+        self.each(&|value| {
+            let integer = match value {
+                Self::Integer(integer) | Self::Radix(integer, _) => Some(integer.clone()),
+                Self::Number(number) if number.fract() == 0. => BigInt::from_f64(*number),
+                _ => None
+            };
+            match integer {
+                Some(integer) if radix == 10 => Ok(Self::Integer(integer)),
+                Some(integer) => Ok(Self::Radix(integer, radix)),
+                None => error!(TypeMismatch, "expected an integer to convert to base {radix}, got {value}")
+            }
+        })
+    }
+    pub fn rational(&self) -> Option<BigRational> {
+        match self {
+            Self::Integer(integer) | Self::Radix(integer, _) => Some(BigRational::from_integer(integer.clone())),
+            Self::Rational(rational) => Some(rational.clone()),
+            _ => None
+        }
+    }
+    /// the value as a float, if it is a real number
+    pub fn number(&self) -> Option<f64> {
+        match self {
+            Self::Number(number) => Some(*number),
+            Self::Integer(integer) | Self::Radix(integer, _) => Some(integer.to_f64().unwrap_or(f64::NAN)),
+            Self::Rational(rational) => Some(rational.to_f64().unwrap_or(f64::NAN)),
+            _ => None
+        }
+    }
+    pub fn bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(bool) => Some(*bool),
+            _ => None
+        }
+    }
+    /// the value with every rational replaced by its floating point approximation
+    pub fn float(&self) -> Self {
+        match self {
+            Self::Integer(_) | Self::Rational(_) | Self::Radix(..) => Self::Number(self.number().unwrap_or(f64::NAN)),
+            Self::Quantity(magnitude, unit) => Self::Quantity(Box::new(magnitude.float()), unit.clone()),
+            Self::Vector(vector, typ) => Self::Vector(vector.iter().map(Self::float).collect(), typ.clone()),
+            Self::Set(set, typ) => Self::Set(set.iter().map(Self::float).collect(), typ.clone()),
+            value => value.clone()
+        }
+    }
+    /// exact for rationals that are squares of rationals
+    pub fn sqrt(&self) -> Result<Self, Error> {
+        if let Some(rational) = self.rational().filter(|rational| !rational.is_negative()) {
+            let (numer, denom) = (rational.numer().sqrt(), rational.denom().sqrt());
+            if &(&numer * &numer) == rational.numer() && &(&denom * &denom) == rational.denom() {
+                return Ok(Self::exact(BigRational::new(numer, denom)))
+            }
+        }
+        builtins::Builtin::Unary(f64::sqrt).call("sqrt", std::slice::from_ref(self))
+    }
     pub fn typ(&self) -> Type {
         match self {
-            Self::Number(_) => Type::Number,
+            Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Complex(_) | Self::Radix(..) => Type::Number(Dimension::default()),
+            Self::Quantity(_, unit) => Type::Number(unit.dimension),
+            Self::Bool(_) => Type::Bool,
             Self::Vector(_, typ) => Type::Vector(Box::new(typ.clone())),
             Self::Set(_, typ) => Type::Set(Box::new(typ.clone())),
         }
     }
     pub fn expr(&self) -> Expr {
         match self {
-            Self::Number(number) => if number.floor() == *number {
-                Expr::Int(*number as i64)
-            } else {
-                Expr::Float(*number)
+            Self::Number(number) => Expr::from(ExprKind::Float(*number)),
+            Self::Integer(integer) | Self::Radix(integer, _) => Expr::from(ExprKind::Int(integer.clone())),
+            Self::Rational(rational) => Expr::binary(Expr::from(ExprKind::Int(rational.numer().clone())), Expr::from(ExprKind::Int(rational.denom().clone())), Token::Div),
+            Self::Complex(complex) => {
+                let imaginary = Expr::binary(Expr::from(ExprKind::Float(complex.im)), Expr::id("i"), Token::Mult);
+                if complex.re == 0. { imaginary } else { Expr::binary(Expr::from(ExprKind::Float(complex.re)), imaginary, Token::Add) }
             }
+            Self::Quantity(magnitude, unit) => Expr::binary(magnitude.expr(), Expr::from(ExprKind::Unit(unit.name.clone())), Token::Mult),
+            Self::Bool(bool) => Expr::id(&bool.to_string()),
             Self::Vector(vector, _) => {
                 let mut values = vec![];
                 for value in vector.iter() {
                     values.push(value.expr());
                 }
-                Expr::Vector(values)
+                Expr::from(ExprKind::Vector(values))
             }
             Self::Set(set, _) => {
                 let mut values = vec![];
                 for value in set.iter() {
                     values.push(value.expr());
                 }
-                Expr::Set(values)
+                Expr::from(ExprKind::Set(values))
+            }
+        }
+    }
+    pub fn vector(values: Vec<Self>) -> Self {
+        let typ = values.first().map(Self::typ).unwrap_or(Type::Number(Dimension::default()));
+        Self::Vector(values, typ)
+    }
+    /// nested sets are merged, so alternatives combine instead of nesting
+    pub fn set(values: Vec<Self>) -> Self {
+        let mut set = HashSet::new();
+        for value in values {
+            match value {
+                Self::Set(values, _) => set.extend(values),
+                value => { set.insert(value); }
+            }
+        }
+        let typ = set.iter().next().map(Self::typ).unwrap_or(Type::Number(Dimension::default()));
+        Self::Set(set, typ)
+    }
+    /// applies `f` to every pair of scalars, sets are combined before vectors,
+    /// two vectors element by element and a scalar with every element of a vector
+    pub fn broadcast(&self, other: &Self, f: &dyn Fn(&Self, &Self) -> Result<Self, Error>) -> Result<Self, Error> {
+        match (self, other) {
+            (Self::Set(set, _), _) => {
+                let mut values = vec![];
+                for value in set.iter() { values.push(value.broadcast(other, f)?); }
+                Ok(Value::set(values))
+            }
+            (_, Self::Set(set, _)) => {
+                let mut values = vec![];
+                for value in set.iter() { values.push(self.broadcast(value, f)?); }
+                Ok(Value::set(values))
+            }
+            (Self::Vector(vector1, _), Self::Vector(vector2, _)) => {
+                if vector1.len() != vector2.len() {
+                    return error!(TypeMismatch, "cannot combine vectors of length {} and {} element-wise", vector1.len(), vector2.len())
+                }
+                let mut values = vec![];
+                for (value1, value2) in vector1.iter().zip(vector2.iter()) { values.push(value1.broadcast(value2, f)?); }
+                Ok(Value::vector(values))
             }
+            (Self::Vector(vector, _), _) => {
+                let mut values = vec![];
+                for value in vector.iter() { values.push(value.broadcast(other, f)?); }
+                Ok(Value::vector(values))
+            }
+            (_, Self::Vector(vector, _)) => {
+                let mut values = vec![];
+                for value in vector.iter() { values.push(self.broadcast(value, f)?); }
+                Ok(Value::vector(values))
+            }
+            _ => f(self, other)
         }
     }
     pub fn binary(&self, other: &Self, op: &Token) -> Result<Self, Error> {
@@ -42,17 +210,154 @@ impl Value {
             (Self::Number(number1), Self::Number(number2)) => match op {
                 Token::Add => Ok(Value::Number(number1 + number2)),
                 Token::Sub => Ok(Value::Number(number1 - number2)),
+                Token::AddSub => if number2 == &0. {
+                    Ok(Value::Number(*number1))
+                } else {
+                    Ok(Value::set(vec![Value::Number(number1 + number2), Value::Number(number1 - number2)]))
+                }
                 Token::Mult => Ok(Value::Number(number1 * number2)),
                 Token::Div => Ok(Value::Number(number1 / number2)),
-                Token::Power => Ok(Value::Number(number1.powf(*number2))),
-                _ => error!("illegal binary operator '{op}'")
+                // a negative base with a fractional exponent has complex roots, like `sqrt`
+                Token::Power => match number1.powf(*number2) {
+                    power if power.is_nan() && !number1.is_nan() && !number2.is_nan() => self.complex_binary(other, op),
+                    power => Ok(Value::Number(power))
+                }
+                Token::Continue => {
+                    let step = if number1 <= number2 { 1. } else { -1. };
+                    let count = (number2 - number1).abs().floor();
+                    if !count.is_finite() || count > MAX_RANGE as f64 { return error!(Limit, "range {number1} ... {number2} is too large") }
+                    Ok(Value::vector((0..=count as usize).map(|i| Value::Number(number1 + step * i as f64)).collect()))
+                }
+                Token::Equal => Ok(Value::Bool(number1 == number2)),
+                Token::Less => Ok(Value::Bool(number1 < number2)),
+                Token::Greater => Ok(Value::Bool(number1 > number2)),
+                Token::LessEqual => Ok(Value::Bool(number1 <= number2)),
+                Token::GreaterEqual => Ok(Value::Bool(number1 >= number2)),
+                _ => error!(TypeMismatch, "illegal binary operator '{op}'")
+            }
+            (Self::Integer(_) | Self::Rational(_) | Self::Radix(..), Self::Integer(_) | Self::Rational(_) | Self::Radix(..)) => self.exact_binary(other, op),
+            (Self::Quantity(..), Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Radix(..) | Self::Complex(_) | Self::Quantity(..)) |
+            (Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Radix(..) | Self::Complex(_), Self::Quantity(..)) => self.quantity_binary(other, op),
+            (Self::Complex(_), Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Radix(..) | Self::Complex(_)) |
+            (Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Radix(..), Self::Complex(_)) => self.complex_binary(other, op),
+            (Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Radix(..), Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Radix(..)) =>
+                Value::Number(self.number().unwrap_or(f64::NAN)).binary(&Value::Number(other.number().unwrap_or(f64::NAN)), op),
+            (Self::Bool(bool1), Self::Bool(bool2)) => match op {
+                Token::Equal => Ok(Value::Bool(bool1 == bool2)),
+                _ => error!(TypeMismatch, "illegal binary operator for booleans '{op}'")
             }
-            (Self::Vector(vector, typ), other) | (other, Self::Vector(vector, typ)) => {
+            (Self::Vector(..) | Self::Set(..), _) | (_, Self::Vector(..) | Self::Set(..)) =>
+                self.broadcast(other, &|left, right| left.binary(right, op)),
+            _ => error!(TypeMismatch, "cannot '{op}' the values of type {} and {}", self.typ(), other.typ())
+        }
+    }
+    /// integers and fractions, results that cannot be exact fall back to floating point
+    fn exact_binary(&self, other: &Self, op: &Token) -> Result<Self, Error> {
+        let (Some(rational1), Some(rational2)) = (self.rational(), other.rational()) else {
+            return error!(TypeMismatch, "cannot '{op}' the values of type {} and {}", self.typ(), other.typ())
+        };
+        let (rational1, rational2) = (&rational1, &rational2);
+        match op {
+            Token::Add => Ok(Value::exact(rational1 + rational2)),
+            Token::Sub => Ok(Value::exact(rational1 - rational2)),
+            Token::AddSub => if rational2.is_zero() {
+                Ok(self.clone())
+            } else {
+                Ok(Value::set(vec![Value::exact(rational1 + rational2), Value::exact(rational1 - rational2)]))
+            }
+            Token::Mult => Ok(Value::exact(rational1 * rational2)),
+            // division by zero keeps its floating point meaning
+            Token::Div if rational2.is_zero() => Value::Number(self.number().unwrap_or(f64::NAN)).binary(&Value::Number(0.), op),
+            Token::Div => Ok(Value::exact(rational1 / rational2)),
+            Token::Power => match rational2.to_integer().to_i32() {
+                Some(exponent) if rational2.is_integer() && exponent.abs() <= MAX_EXPONENT && !(rational1.is_zero() && exponent < 0) =>
+                    Ok(Value::exact(rational1.pow(exponent))),
+                _ => Value::Number(self.number().unwrap_or(f64::NAN)).binary(&Value::Number(other.number().unwrap_or(f64::NAN)), op)
+            }
+            Token::Continue => {
+                let step = BigRational::from_integer(BigInt::from(if rational1 <= rational2 { 1 } else { -1 }));
+                let count = (rational2 - rational1).abs().floor().to_usize().unwrap_or(usize::MAX);
+                if count > MAX_RANGE { return error!(Limit, "range {rational1} ... {rational2} is too large") }
                 let mut values = vec![];
-                for value in vector.iter() { values.push(value.binary(other, op)?); }
-                Ok(Value::Vector(values, typ.clone()))
+                let mut value = rational1.clone();
+                for _ in 0..=count {
+                    values.push(Value::exact(value.clone()));
+                    value += &step;
+                }
+                Ok(Value::vector(values))
             }
-            _ => error!("cannot '{op}' the values of type {} and {}", self.typ(), other.typ())
+            Token::Equal => Ok(Value::Bool(rational1 == rational2)),
+            Token::Less => Ok(Value::Bool(rational1 < rational2)),
+            Token::Greater => Ok(Value::Bool(rational1 > rational2)),
+            Token::LessEqual => Ok(Value::Bool(rational1 <= rational2)),
+            Token::GreaterEqual => Ok(Value::Bool(rational1 >= rational2)),
+            _ => error!(TypeMismatch, "illegal binary operator '{op}'")
+        }
+    }
+    /// sums and comparisons are in the unit of the left side, products of two quantities in SI units
+    fn quantity_binary(&self, other: &Self, op: &Token) -> Result<Self, Error> {
+        let ((magnitude1, unit1), (magnitude2, unit2)) = (self.parts(), other.parts());
+        match op {
+            Token::Mult | Token::Div if unit2.dimension.is_none() => Ok(Value::quantity(magnitude1.binary(&magnitude2, op)?, unit1)),
+            Token::Mult if unit1.dimension.is_none() => Ok(Value::quantity(magnitude1.binary(&magnitude2, op)?, unit2)),
+            Token::Mult | Token::Div => {
+                let magnitude1 = magnitude1.binary(&Value::exact(unit1.scale), &Token::Mult)?;
+                let magnitude2 = magnitude2.binary(&Value::exact(unit2.scale), &Token::Mult)?;
+                let dimension = if op == &Token::Mult { unit1.dimension.mul(&unit2.dimension) } else { unit1.dimension.div(&unit2.dimension) };
+                Ok(Value::quantity(magnitude1.binary(&magnitude2, op)?, Unit::si(dimension)))
+            }
+            Token::Power => {
+                let exponent = magnitude2.rational().filter(|exponent| unit2.dimension.is_none() && exponent.is_integer());
+                let Some(exponent) = exponent.and_then(|exponent| exponent.to_integer().to_i32()) else {
+                    return Err(UnitError::Exponent(other.to_string()).into())
+                };
+                let magnitude = magnitude1.binary(&Value::exact(unit1.scale), &Token::Mult)?;
+                Ok(Value::quantity(magnitude.binary(&magnitude2, op)?, Unit::si(unit1.dimension.pow(exponent))))
+            }
+            _ => {
+                if unit1.dimension != unit2.dimension {
+                    return Err(UnitError::Incompatible { left: unit1.dimension, right: unit2.dimension }.into())
+                }
+                // converting between units is approximate, like in `convert`
+                let magnitude2 = match unit2.scale == unit1.scale {
+                    true => magnitude2,
+                    false => match magnitude2.binary(&Value::exact(unit2.scale / &unit1.scale), &Token::Mult)? {
+                        magnitude @ Value::Rational(_) => magnitude.float(),
+                        magnitude => magnitude
+                    }
+                };
+                magnitude1.binary(&magnitude2, op)?.each(&|value| match value {
+                    Value::Bool(_) => Ok(value.clone()),
+                    value => Ok(Value::quantity(value.clone(), unit1.clone()))
+                })
+            }
+        }
+    }
+    fn complex_binary(&self, other: &Self, op: &Token) -> Result<Self, Error> {
+        let (Some(complex1), Some(complex2)) = (self.as_complex(), other.as_complex()) else {
+            return error!(TypeMismatch, "cannot '{op}' the values of type {} and {}", self.typ(), other.typ())
+        };
+        match op {
+            Token::Add => Ok(Value::complex(complex1 + complex2)),
+            Token::Sub => Ok(Value::complex(complex1 - complex2)),
+            Token::AddSub => if complex2 == Complex64::new(0., 0.) {
+                Ok(self.clone())
+            } else {
+                Ok(Value::set(vec![Value::complex(complex1 + complex2), Value::complex(complex1 - complex2)]))
+            }
+            Token::Mult => Ok(Value::complex(complex1 * complex2)),
+            Token::Div => Ok(Value::complex(complex1 / complex2)),
+            // integer powers are repeated multiplication, so `i ^ 2` is exactly -1
+            Token::Power => match other.rational().filter(|exponent| exponent.is_integer()).and_then(|exponent| exponent.to_integer().to_i32()) {
+                Some(exponent) => Ok(Value::complex(complex1.powi(exponent))),
+                // the square root is exact where `powc` leaves a rounding error in the real part
+                None if complex2 == Complex64::new(0.5, 0.) => Ok(Value::complex(complex1.sqrt())),
+                None => Ok(Value::complex(complex1.powc(complex2)))
+            }
+            Token::Equal => Ok(Value::Bool(complex1 == complex2)),
+            Token::Less | Token::Greater | Token::LessEqual | Token::GreaterEqual =>
+                error!(Domain, "cannot order the complex numbers {self} and {other}"),
+            _ => error!(TypeMismatch, "illegal binary operator for complex numbers '{op}'")
         }
     }
     pub fn unary(&self, op: &Token) -> Result<Self, Error> {
@@ -60,7 +365,38 @@ impl Value {
             Self::Number(number) => match op {
                 Token::Sub => Ok(Self::Number(-number)),
                 Token::Percent => Ok(Self::Number(number / 100.)),
-                _ => error!("illegal unary operator for number '{op}'")
+                Token::Fraction => Ok(Self::Number(builtins::factorial(*number)?)),
+                _ => error!(TypeMismatch, "illegal unary operator for number '{op}'")
+            }
+            Self::Radix(integer, _) => Self::Integer(integer.clone()).unary(op),
+            Self::Integer(integer) => match op {
+                Token::Sub => Ok(Self::Integer(-integer)),
+                Token::Percent => Ok(Self::exact(BigRational::new(integer.clone(), BigInt::from(100)))),
+                Token::Fraction => match integer.to_u64() {
+                    Some(n) if n <= MAX_FACTORIAL => Ok(Self::Integer((2..=n).fold(BigInt::from(1), |acc, i| acc * i))),
+                    Some(_) => error!(Limit, "factorial of {integer} is too large"),
+                    None => error!(Domain, "factorial of the negative integer {integer} is undefined")
+                }
+                _ => error!(TypeMismatch, "illegal unary operator for number '{op}'")
+            }
+            Self::Rational(rational) => match op {
+                Token::Sub => Ok(Self::Rational(-rational)),
+                Token::Percent => Ok(Self::Rational(rational / BigInt::from(100))),
+                Token::Fraction => Value::Number(self.number().unwrap_or(f64::NAN)).unary(op),
+                _ => error!(TypeMismatch, "illegal unary operator for number '{op}'")
+            }
+            Self::Complex(complex) => match op {
+                Token::Sub => Ok(Self::Complex(-complex)),
+                Token::Percent => Ok(Self::Complex(complex / 100.)),
+                _ => error!(TypeMismatch, "illegal unary operator for complex number '{op}'")
+            }
+            Self::Quantity(magnitude, unit) => match op {
+                Token::Sub | Token::Percent => Ok(Self::Quantity(Box::new(magnitude.unary(op)?), unit.clone())),
+                _ => error!(TypeMismatch, "illegal unary operator for quantity '{op}'")
+            }
+            Self::Bool(bool) => match op {
+                Token::Not => Ok(Self::Bool(!bool)),
+                _ => error!(TypeMismatch, "illegal unary operator for boolean '{op}'")
             }
             Self::Vector(vector, typ) => {
                 let mut values = vec![];
@@ -74,32 +410,152 @@ impl Value {
             }
         }
     }
+    pub fn absolute(&self) -> Result<Self, Error> {
+        match self {
+            Self::Number(number) => Ok(Self::Number(number.abs())),
+            Self::Integer(integer) | Self::Radix(integer, _) => Ok(Self::Integer(integer.abs())),
+            Self::Rational(rational) => Ok(Self::Rational(rational.abs())),
+            Self::Complex(complex) => Ok(Self::Number(complex.norm())),
+            Self::Quantity(magnitude, unit) => Ok(Self::Quantity(Box::new(magnitude.absolute()?), unit.clone())),
+            Self::Bool(_) => error!(TypeMismatch, "cannot evaluate the absolute value of a {}", self.typ()),
+            Self::Vector(vector, _) => {
+                let mut sum: Option<Value> = None;
+                for value in vector.iter() {
+                    let norm = value.absolute()?;
+                    if !matches!(norm, Self::Number(_) | Self::Integer(_) | Self::Rational(_) | Self::Quantity(..)) {
+                        return error!(TypeMismatch, "cannot evaluate the norm of a {}", self.typ())
+                    }
+                    let square = norm.binary(&norm, &Token::Mult)?;
+                    sum = Some(match sum {
+                        Some(sum) => sum.binary(&square, &Token::Add)?,
+                        None => square
+                    });
+                }
+                sum.unwrap_or(Value::integer(0)).sqrt()
+            }
+            Self::Set(set, _) => Ok(Self::integer(set.len() as i64)),
+        }
+    }
+    /// applies `f` to every element of a vector or set
+    pub fn each(&self, f: &dyn Fn(&Self) -> Result<Self, Error>) -> Result<Self, Error> {
+        match self {
+            Self::Vector(vector, typ) => {
+                let mut values = vec![];
+                for value in vector.iter() { values.push(value.each(f)?); }
+                Ok(Value::Vector(values, typ.clone()))
+            }
+            Self::Set(set, typ) => {
+                let mut values = HashSet::new();
+                for value in set.iter() { values.insert(value.each(f)?); }
+                Ok(Value::Set(values, typ.clone()))
+            }
+            value => f(value)
+        }
+    }
+    pub fn map(&self, f: &dyn Fn(f64) -> Result<f64, Error>) -> Result<Self, Error> {
+        self.each(&|value| match value.number() {
+            Some(number) => Ok(Self::Number(f(number)?)),
+            None => error!(Domain, "expected real number, got {value}")
+        })
+    }
+    pub fn zip(&self, other: &Self, f: &dyn Fn(f64, f64) -> Result<f64, Error>) -> Result<Self, Error> {
+        self.broadcast(other, &|left, right| match (left.number(), right.number()) {
+            (Some(number1), Some(number2)) => Ok(Self::Number(f(number1, number2)?)),
+            _ => error!(Domain, "expected real numbers, got {left} and {right}")
+        })
+    }
 }
-impl Eq for Value {
-    fn assert_receiver_is_total_eq(&self) {}
+impl Value {
+    pub fn format(&self, format: &Format) -> String {
+        match self {
+            Self::Number(number) => format.number(*number),
+            Self::Integer(integer) => format.integer(integer),
+            Self::Radix(integer, radix) => {
+                let prefix = match radix { 16 => "0x", 8 => "0o", 2 => "0b", _ => "" };
+                let sign = if integer.is_negative() { "-" } else { "" };
+                format!("{sign}{prefix}{}", integer.abs().to_str_radix(*radix).to_uppercase())
+            }
+            Self::Rational(rational) => format!("{}/{}", format.integer(rational.numer()), format.integer(rational.denom())),
+            Self::Complex(complex) => {
+                let coefficient = |im: f64| if im == 1. { String::new() } else { format.number(im) };
+                if complex.re == 0. {
+                    format!("{}i", if complex.im == -1. { "-".to_string() } else { coefficient(complex.im) })
+                } else if complex.im < 0. {
+                    format!("{} - {}i", format.number(complex.re), coefficient(-complex.im))
+                } else {
+                    format!("{} + {}i", format.number(complex.re), coefficient(complex.im))
+                }
+            }
+            Self::Quantity(magnitude, unit) => match magnitude.as_ref() {
+                Self::Complex(_) => format!("({}) {}", magnitude.format(format), unit.name),
+                magnitude => format!("{} {}", magnitude.format(format), unit.name)
+            }
+            Self::Bool(bool) => bool.to_string(),
+            Self::Vector(vector, _) => format!("( {} )", vector.iter().map(|x| x.format(format)).collect::<Vec<String>>().join(" ")),
+            Self::Set(set, _) => {
+                let mut values = set.iter().collect::<Vec<&Value>>();
+                values.sort_by(|a, b| match (a.as_complex(), b.as_complex()) {
+                    (Some(a), Some(b)) => a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)),
+                    _ => a.to_string().cmp(&b.to_string())
+                });
+                if values.is_empty() { return "{}".to_string() }
+                format!("{{ {} }}", values.iter().map(|x| x.format(format)).collect::<Vec<String>>().join(" "))
+            }
+        }
+    }
+}
+impl From<f64> for Value {
+    fn from(number: f64) -> Self {
+        Self::Number(number)
+    }
+}
+impl From<i64> for Value {
+    fn from(number: i64) -> Self {
+        Self::integer(number)
+    }
+}
+impl From<bool> for Value {
+    fn from(bool: bool) -> Self {
+        Self::Bool(bool)
+    }
 }
+impl Eq for Value {}
+/// agrees with the derived equality, both zeros of a float hash alike and sets only by their size
 impl Hash for Value {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {}
-    fn hash_slice<H: std::hash::Hasher>(data: &[Self], state: &mut H) where Self: Sized, {}
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        let float = |number: f64, state: &mut H| if number == 0. { 0f64 } else { number }.to_bits().hash(state);
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Self::Number(number) => float(*number, state),
+            Self::Integer(integer) | Self::Radix(integer, _) => integer.hash(state),
+            Self::Rational(rational) => rational.hash(state),
+            Self::Complex(complex) => {
+                float(complex.re, state);
+                float(complex.im, state);
+            }
+            Self::Quantity(magnitude, _) => magnitude.hash(state),
+            Self::Bool(bool) => bool.hash(state),
+            Self::Vector(vector, _) => vector.hash(state),
+            Self::Set(set, _) => set.len().hash(state),
+        }
+    }
 }
 impl Display for Value {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::Number(number) => write!(f, "{number}"),
-            Self::Vector(vector, _) => write!(f, "( {} )", vector.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" ")),
-            Self::Set(set, _) => write!(f, "{{ {} }}", set.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" ")),
-        }
+        write!(f, "{}", self.format(&Format::default()))
     }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
-    Number, Vector(Box<Type>), Set(Box<Type>)
+    Number(Dimension), Bool, Vector(Box<Type>), Set(Box<Type>)
 }
 impl Display for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Number => write!(f, "number"),
+            Self::Number(dimension) if dimension.is_none() => write!(f, "number"),
+            Self::Number(dimension) => write!(f, "number in {dimension}"),
+            Self::Bool => write!(f, "boolean"),
             Self::Vector(typ) => write!(f, "vector of {typ}"),
             Self::Set(typ) => write!(f, "set of {typ}"),
         }
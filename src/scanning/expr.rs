@@ -1,55 +1,168 @@
 use std::fmt::Display;
 
+use num_bigint::BigInt;
+
 use crate::*;
+use runtime::format::Format;
+use scanning::span::Span;
 use scanning::token::Token;
 
 #[derive(Debug, Clone, PartialEq)]
-pub enum Expr {
-    ID(String), Int(i64), Float(f64), Continue,
+pub enum ExprKind {
+    ID(String), Int(BigInt), Float(f64), Continue, Unit(String),
     BinaryOperation { left: Box<Expr>, right: Box<Expr>, op: Token },
     UnaryOperation { expr: Box<Expr>, op: Token }, UnaryOperationRight { expr: Box<Expr>, op: Token },
     Vector(Vec<Expr>), Set(Vec<Expr>),
-    Absolute(Box<Expr>)
+    Absolute(Box<Expr>),
+    Call { id: String, args: Vec<Expr> },
+    Store { target: Box<Expr>, expr: Box<Expr> },
+    /// input the parser skipped after a syntax error
+    Error,
+}
+
+/// an expression with the part of the input it was parsed from,
+/// the span is not part of its identity, so rules match wherever an expression was written
+#[derive(Debug, Clone)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+impl PartialEq for Expr {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+impl From<ExprKind> for Expr {
+    fn from(kind: ExprKind) -> Self {
+        Self { kind, span: Span::default() }
+    }
+}
+impl Expr {
+    pub fn new(kind: ExprKind, span: Span) -> Self {
+        Self { kind, span }
+    }
+    pub fn id(id: &str) -> Self {
+        Self::from(ExprKind::ID(id.to_string()))
+    }
+    pub fn int(v: i64) -> Self {
+        Self::from(ExprKind::Int(BigInt::from(v)))
+    }
+    pub fn binary(left: Expr, right: Expr, op: Token) -> Self {
+        let span = left.span.to(&right.span);
+        Self::new(ExprKind::BinaryOperation { left: Box::new(left), right: Box::new(right), op }, span)
+    }
+    pub fn unary(expr: Expr, op: Token) -> Self {
+        let span = expr.span;
+        Self::new(ExprKind::UnaryOperation { expr: Box::new(expr), op }, span)
+    }
+    pub fn call(id: &str, args: Vec<Expr>) -> Self {
+        Self::from(ExprKind::Call { id: id.to_string(), args })
+    }
+    pub fn name(&self) -> &str {
+        self.kind.name()
+    }
+    pub fn count(&self) -> usize {
+        self.kind.count()
+    }
+    /// free identifiers in order of appearance, call names excluded
+    pub fn ids(&self) -> Vec<String> {
+        let mut ids = vec![];
+        self.kind.collect_ids(&mut ids);
+        ids
+    }
 }
 impl Expr {
+    /// like `Display`, with the numbers printed in `format`
+    pub fn format(&self, format: &Format) -> String {
+        self.kind.format(format)
+    }
+}
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind)
+    }
+}
+
+impl ExprKind {
     pub fn name(&self) -> &str {
         match self {
             Self::ID(_) => "identifier",
             Self::Int(_) => "integer",
             Self::Float(_) => "number",
             Self::Continue => "continuation",
+            Self::Unit(_) => "unit",
             Self::BinaryOperation { left:_, right:_, op:_ } => "binary operation",
             Self::UnaryOperation { expr:_, op:_ } => "unary operation",
             Self::UnaryOperationRight { expr:_, op:_ } => "unary operation (right sided)",
             Self::Vector(_) => "vector",
             Self::Set(_) => "set",
             Self::Absolute(_) => "absolute expression",
+            Self::Call { id:_, args:_ } => "call",
+            Self::Store { target:_, expr:_ } => "store",
+            Self::Error => "syntax error",
         }
     }
     pub fn count(&self) -> usize {
         match self {
-            Self::ID(_) | Self::Int(_) | Self::Float(_) | Self::Continue => 1,
-            Self::BinaryOperation { left, right, op:_ } => 1 + left.count() + right.count(),
+            Self::ID(_) | Self::Int(_) | Self::Float(_) | Self::Continue | Self::Unit(_) | Self::Error => 1,
+            Self::BinaryOperation { left, right, op:_ } | Self::Store { target: left, expr: right } => 1 + left.count() + right.count(),
             Self::UnaryOperation { expr, op:_ } | Self::UnaryOperationRight { expr, op:_ } |
             Self::Absolute(expr) => 1 + expr.count(),
             Self::Vector(vector) => 1 + vector.iter().map(|x| x.count()).collect::<Vec<usize>>().iter().sum::<usize>(),
-            Self::Set(set) => 1 + set.iter().map(|x| x.count()).collect::<Vec<usize>>().iter().sum::<usize>(),
+            Self::Set(set) | Self::Call { id:_, args: set } => 1 + set.iter().map(|x| x.count()).collect::<Vec<usize>>().iter().sum::<usize>(),
+        }
+    }
+    fn collect_ids(&self, ids: &mut Vec<String>) {
+        match self {
+            Self::ID(id) => if !ids.contains(id) { ids.push(id.clone()) }
+            Self::Int(_) | Self::Float(_) | Self::Continue | Self::Unit(_) | Self::Error => {}
+            Self::BinaryOperation { left, right, op:_ } | Self::Store { target: left, expr: right } => {
+                left.kind.collect_ids(ids);
+                right.kind.collect_ids(ids);
+            }
+            Self::UnaryOperation { expr, op:_ } | Self::UnaryOperationRight { expr, op:_ } |
+            Self::Absolute(expr) => expr.kind.collect_ids(ids),
+            Self::Vector(exprs) | Self::Set(exprs) | Self::Call { id:_, args: exprs } => for expr in exprs.iter() {
+                expr.kind.collect_ids(ids);
+            }
         }
     }
 }
-impl Display for Expr {
+impl ExprKind {
+    pub fn format(&self, format: &Format) -> String {
+        let join = |exprs: &[Expr], seperator| exprs.iter().map(|x| x.format(format)).collect::<Vec<String>>().join(seperator);
+        match self {
+            Self::Int(v) => format.integer(v),
+            Self::Float(v) => format.number(*v),
+            Self::ID(_) | Self::Continue | Self::Unit(_) | Self::Error => self.to_string(),
+            Self::BinaryOperation { left, right, op } => format!("({} {op} {})", left.format(format), right.format(format)),
+            Self::UnaryOperation { expr, op } => format!("({op} {})", expr.format(format)),
+            Self::UnaryOperationRight { expr, op } => format!("({} {op})", expr.format(format)),
+            Self::Vector(vector) => format!("( {} )", join(vector, " ")),
+            Self::Set(set) => format!("{{ {} }}", join(set, " ")),
+            Self::Absolute(expr) => format!("| {} |", expr.format(format)),
+            Self::Call { id, args } => format!("{id}({})", join(args, ", ")),
+            Self::Store { target, expr } => format!("{} <- {}", target.format(format), expr.format(format)),
+        }
+    }
+}
+impl Display for ExprKind {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::ID(id) => write!(f, "{id}"),
             Self::Int(v) => write!(f, "{v}"),
             Self::Float(v) => write!(f, "{v}"),
             Self::Continue => write!(f, "..."),
+            Self::Unit(unit) => write!(f, "{unit}"),
             Self::BinaryOperation { left, right, op } => write!(f, "({left} {op} {right})"),
             Self::UnaryOperation { expr, op } => write!(f, "({op} {expr})"),
             Self::UnaryOperationRight { expr, op } => write!(f, "({expr} {op})"),
             Self::Vector(vector) => write!(f, "( {} )", vector.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" ")),
             Self::Set(set) => write!(f, "{{ {} }}", set.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" ")),
             Self::Absolute(expr) => write!(f, "| {expr} |"),
+            Self::Call { id, args } => write!(f, "{id}({})", args.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(", ")),
+            Self::Store { target, expr } => write!(f, "{target} <- {expr}"),
+            Self::Error => write!(f, "<error>"),
         }
     }
 }
\ No newline at end of file
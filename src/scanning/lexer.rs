@@ -1,18 +1,28 @@
 use crate::*;
 use logos::Logos;
+use scanning::span::Span;
 use scanning::token::Token;
 
-pub type LexerResult = Result<Vec<Token>, String>;
+pub type LexerResult = Result<Vec<(Token, Span)>, Error>;
 
 pub fn lex(input: String) -> LexerResult {
-    let mut lexer = Token::lexer(input.as_str());
-    let mut tokens = vec![];
-    loop {
-        let Some(token) = lexer.next() else { break };
+    let (tokens, errors) = lex_all(&input);
+    match errors.into_iter().next() {
+        Some(e) => Err(e),
+        None => Ok(tokens)
+    }
+}
+
+/// keeps going after invalid input, which stays in the tokens as `Token::Error`
+pub fn lex_all(input: &str) -> (Vec<(Token, Span)>, Vec<Error>) {
+    let mut lexer = Token::lexer(input);
+    let (mut tokens, mut errors) = (vec![], vec![]);
+    while let Some(token) = lexer.next() {
+        let span = Span::new(lexer.span().start, lexer.span().end);
         if token == Token::Error {
-            return error!("unexpected {:?}", lexer.slice().to_string())
+            errors.push(Error::new(ErrorKind::Lex, format!("unexpected {:?}", lexer.slice().to_string())).at(span));
         }
-        tokens.push(token);
+        tokens.push((token, span));
     }
-    Ok(tokens)
-}
\ No newline at end of file
+    (tokens, errors)
+}
@@ -1,153 +1,393 @@
 use crate::*;
+use runtime::units;
+use scanning::span::Span;
 use scanning::token::Token;
-use scanning::expr::Expr;
+use scanning::expr::{Expr, ExprKind};
 
 pub type ParseResult = Result<Expr, Error>;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity { Left, Right, None }
+
+/// binding power and associativity of the binary operators, higher binds tighter
+pub fn infix(op: &Token) -> Option<(u8, Associativity)> {
+    Some(match op {
+        Token::Store => (1, Associativity::Right),
+        Token::Into => (2, Associativity::Left),
+        Token::Equal | Token::Less | Token::Greater | Token::LessEqual | Token::GreaterEqual => (4, Associativity::None),
+        Token::Continue => (5, Associativity::None),
+        Token::Add | Token::Sub | Token::AddSub => (6, Associativity::Left),
+        Token::Mult | Token::Div => (7, Associativity::Left),
+        Token::Power => (9, Associativity::Right),
+        _ => return None
+    })
+}
+/// the binding power of the operand of a prefix operator, so `~` negates a comparison and `-2^2` is `-(2^2)`
+pub fn prefix(op: &Token) -> Option<u8> {
+    match op {
+        Token::Not => Some(3),
+        Token::Add | Token::Sub => Some(8),
+        _ => None
+    }
+}
+pub fn postfix(op: &Token) -> Option<u8> {
+    match op {
+        Token::Fraction | Token::Percent => Some(10),
+        _ => None
+    }
+}
+
 pub struct Parser {
-    tokens: Vec<Token>,
+    tokens: Vec<(Token, Span)>,
     idx: usize,
+    /// syntax errors the parser recovered from
+    errors: Vec<Error>,
 }
 impl Parser {
-    pub fn new(tokens: Vec<Token>) -> Self {
-        Self { tokens, idx: 0 }
+    pub fn new(tokens: Vec<(Token, Span)>) -> Self {
+        Self { tokens, idx: 0, errors: vec![] }
     }
     pub fn advance(&mut self) { self.idx += 1; }
     pub fn advance_if(&mut self, cond: bool) {
         if cond { self.advance(); }
     }
     pub fn token(&self) -> Option<&Token> {
-        self.tokens.get(self.idx)
+        self.tokens.get(self.idx).map(|(token, _)| token)
+    }
+    pub fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx + 1).map(|(token, _)| token)
+    }
+    /// the span of the current token, or the end of the input
+    pub fn span(&self) -> Span {
+        match self.tokens.get(self.idx) {
+            Some((_, span)) => *span,
+            None => self.tokens.last().map_or(Span::default(), |(_, span)| Span::new(span.end, span.end + 1))
+        }
     }
-    pub fn expect_token(&mut self, expected_token: Token) -> Result<(), String> {
+    /// from the token at `start` to the last token consumed
+    pub fn span_from(&self, start: usize) -> Span {
+        match (self.tokens.get(start), self.idx.checked_sub(1).and_then(|idx| self.tokens.get(idx))) {
+            (Some((_, first)), Some((_, last))) => Span::new(first.start, last.end),
+            _ => Span::default()
+        }
+    }
+    pub fn expect_token(&mut self, expected_token: Token) -> Result<(), Error> {
         if let Some(token) = self.token() {
-            if token != &expected_token { return error!("expected token '{expected_token}', got '{token}'") }
+            if token != &expected_token { return error!(Parse, "expected token '{expected_token}', got '{token}'") }
             Ok(())
         } else {
-            error!("unexpected end, expected token '{expected_token}'")
+            error!(Parse, "unexpected end, expected token '{expected_token}'")
+        }
+    }
+    /// skips to the next separator, closing bracket or token `stop` accepts, outside of the brackets skipped on the way
+    pub fn synchronize(&mut self, stop: fn(&Token) -> bool) {
+        let mut depth = 0;
+        while let Some(token) = self.token() {
+            match token {
+                Token::EvalIn | Token::SetIn => depth += 1,
+                Token::EvalOut | Token::SetOut | Token::Seperator if depth == 0 => break,
+                Token::EvalOut | Token::SetOut => depth -= 1,
+                token if depth == 0 && stop(token) => break,
+                _ => {}
+            }
+            self.advance();
         }
     }
-    pub fn expect_token_advance(&mut self, expected_token: Token) -> Result<(), String> {
-        self.expect_token(expected_token)?;
-        self.advance();
-        Ok(())
+    /// an element of a bracketed list, on a syntax error it is recorded and the element becomes an error node
+    pub fn element(&mut self) -> Expr {
+        self.recover(|_| false)
+    }
+    /// an expression that becomes an error node on a syntax error, the input is skipped up to `stop`
+    pub fn recover(&mut self, stop: fn(&Token) -> bool) -> Expr {
+        let start = self.idx;
+        match self.expr() {
+            Ok(expr) => expr,
+            Err(e) => {
+                self.errors.push(e.at(self.span()));
+                self.synchronize(stop);
+                Expr::new(ExprKind::Error, self.span_from(start))
+            }
+        }
     }
+    /// a missing closing bracket is recorded, a wrong one is consumed as well,
+    /// the bracketed expression from `start` is `kind` or an error node if it was cut short
+    pub fn close(&mut self, closing: Token, kind: ExprKind, start: usize) -> Expr {
+        match self.expect_token(closing) {
+            Ok(()) => {
+                self.advance();
+                Expr::new(kind, self.span_from(start))
+            }
+            Err(e) => {
+                self.errors.push(e.at(self.span()));
+                self.advance_if(matches!(self.token(), Some(Token::EvalOut | Token::SetOut)));
+                Expr::new(ExprKind::Error, self.span_from(start))
+            }
+        }
+    }
+    /// every syntax error in order, with the expression parsed around them,
+    /// after an error the parser goes on at the next operator or separator outside of brackets
+    pub fn parse_all(&mut self) -> (Expr, Vec<Error>) {
+        let (mut start, mut segments) = (0, vec![]);
+        let mut result = self.expr();
+        let expr = loop {
+            match result {
+                Ok(expr) if self.token().is_none() => break expr,
+                Ok(_) => match self.token() {
+                    // the lexer or a missing closing bracket reported the token already
+                    Some(Token::Error) | None => {}
+                    Some(_) if self.errors.last().and_then(|e| e.span) == Some(self.span()) => {}
+                    Some(token @ (Token::ID(_) | Token::Int(_) | Token::Float(_) | Token::EvalIn)) => {
+                        let e = Error::new(ErrorKind::Parse, format!("cannot handel '{token}' at the end of input")).note("write `*` to multiply".to_string());
+                        self.errors.push(e.at(self.span()));
+                    }
+                    Some(token) => {
+                        let e = Error::new(ErrorKind::Parse, format!("cannot handel '{token}' at the end of input"));
+                        self.errors.push(e.at(self.span()));
+                    }
+                }
+                Err(e) => self.errors.push(e.at(self.span())),
+            }
+            if self.token() != Some(&Token::Seperator) {
+                self.advance_if(self.token().is_some());
+                self.synchronize(|token| infix(token).is_some());
+            }
+            let skipped = Expr::new(ExprKind::Error, self.span_from(start));
+            result = match self.token() {
+                Some(Token::Seperator) => {
+                    self.advance();
+                    segments.push(skipped);
+                    start = self.idx;
+                    self.expr()
+                }
+                Some(_) => self.binary_from(skipped, start, 0),
+                None => break skipped
+            };
+        };
+        let expr = match segments.is_empty() {
+            true => expr,
+            false => Expr::new(ExprKind::Vector([segments, vec![expr]].concat()), self.span_from(0))
+        };
+        self.errors.sort_by_key(|e| e.span.map(|span| span.start));
+        (expr, std::mem::take(&mut self.errors))
+    }
+    /// errors point at the token the parser stopped at
     pub fn parse(&mut self) -> ParseResult {
-        let expr = self.expr()?;
-        if let Some(token) = self.token() {
-            return error!("cannot handel '{token}' at the end of input")
+        match self.parse_all() {
+            (_, errors) if !errors.is_empty() => Err(errors[0].clone()),
+            (expr, _) => Ok(expr)
         }
-        Ok(expr)
     }
     pub fn expr(&mut self) -> ParseResult {
-        self.arith()
+        self.binary(0)
     }
-    pub fn arith(&mut self) -> ParseResult {
-        let mut left = self.term()?;
-        while let Some(token) = self.token() {
-            if ![Token::Add, Token::Sub, Token::AddSub].contains(&token) { break }
-            let op = token.clone();
-            self.advance();
-            let right = Box::new(self.term()?);
-            left = Expr::BinaryOperation { left: Box::from(left), right, op }
-        }
-        Ok(left)
+    /// precedence climbing over the operators of `infix`, `prefix` and `postfix`,
+    /// only operators binding at least as tight as `min` are consumed
+    pub fn binary(&mut self, min: u8) -> ParseResult {
+        let start = self.idx;
+        let left = match self.token().and_then(prefix) {
+            Some(power) => {
+                let op = self.token().cloned().unwrap_or(Token::Error);
+                self.advance();
+                let expr = self.binary(power)?;
+                Expr::new(ExprKind::UnaryOperation { expr: Box::new(expr), op }, self.span_from(start))
+            }
+            None => self.operand()?
+        };
+        self.binary_from(left, start, min)
     }
-    pub fn term(&mut self) -> ParseResult {
-        let mut left = self.pow()?;
-        while let Some(token) = self.token() {
-            if ![Token::Mult, Token::Div].contains(&token) { break }
-            let op = token.clone();
+    /// the operators following `left`, which was parsed from the token at `start`
+    pub fn binary_from(&mut self, mut left: Expr, start: usize, min: u8) -> ParseResult {
+        while let Some(op) = self.token().cloned() {
+            if let Some(power) = postfix(&op) {
+                if power < min { break }
+                self.advance();
+                left = Expr::new(ExprKind::UnaryOperationRight { expr: Box::new(left), op }, self.span_from(start));
+                continue
+            }
+            let Some((power, associativity)) = infix(&op) else { break };
+            if power < min { break }
             self.advance();
-            let right = Box::new(self.pow()?);
-            left = Expr::BinaryOperation { left: Box::from(left), right, op }
+            left = match op {
+                Token::Store => self.store(left)?,
+                Token::Into => self.conversion(left)?,
+                op => {
+                    let right = self.binary(if associativity == Associativity::Right { power } else { power + 1 })?;
+                    Expr::binary(left, right, op)
+                }
+            };
+            if associativity == Associativity::None && self.token().and_then(infix).map(|(next, _)| next) == Some(power) {
+                return error!(Parse, "cannot chain '{}' without brackets", self.token().cloned().unwrap_or(Token::Error))
+            }
         }
         Ok(left)
     }
-    pub fn pow(&mut self) -> ParseResult {
-        let mut left = self.factor()?;
-        while let Some(token) = self.token() {
-            if token != &Token::Power { break }
-            let op = token.clone();
-            self.advance();
-            let right = Box::new(self.factor()?);
-            left = Expr::BinaryOperation { left: Box::from(left), right, op }
+    /// `target <- expr`, the target is a variable or a function with identifiers as parameters
+    pub fn store(&mut self, target: Expr) -> ParseResult {
+        match &target.kind {
+            ExprKind::ID(_) => {}
+            ExprKind::Call { id:_, args } => for arg in args.iter() {
+                if !matches!(arg.kind, ExprKind::ID(_)) { return Err(Error::new(ErrorKind::Parse, format!("expected identifier as parameter, got {} `{arg}`", arg.name())).at(arg.span)) }
+            }
+            _ => return Err(Error::new(ErrorKind::Parse, format!("cannot store into {} `{target}`", target.name())).at(target.span))
         }
-        Ok(left)
+        let start = self.idx;
+        let (power, _) = infix(&Token::Store).unwrap_or((0, Associativity::Right));
+        let expr = self.binary(power)?;
+        let span = target.span.to(&self.span_from(start));
+        Ok(Expr::new(ExprKind::Store { target: Box::new(target), expr: Box::new(expr) }, span))
     }
-    pub fn factor(&mut self) -> ParseResult {
-        if let Some(token) = self.token() {
-            if [Token::Add, Token::Sub].contains(&token) {
-                let op = token.clone();
+    /// `target -> unit` or `target -> hex`
+    pub fn conversion(&mut self, target: Expr) -> ParseResult {
+        let start = self.idx;
+        if let Some(Token::ID(id)) = self.token() {
+            if ["hex", "oct", "bin", "dec"].contains(&id.as_str()) {
+                let radix = Expr::new(ExprKind::ID(id.clone()), self.span());
                 self.advance();
-                return Ok(Expr::UnaryOperation { expr: Box::from(self.fraction()?), op })
+                return Ok(Expr::binary(target, radix, Token::Into))
             }
         }
-        self.fraction()
+        let Some(unit) = self.unit(true)? else {
+            return match self.token() {
+                Some(token) => error!(Parse, "expected a unit after '{}', got '{token}'", Token::Into),
+                None => error!(Parse, "expected a unit after '{}'", Token::Into)
+            }
+        };
+        Ok(Expr::binary(target, Expr::new(ExprKind::Unit(unit), self.span_from(start)), Token::Into))
     }
-    pub fn fraction(&mut self) -> ParseResult {
-        let expr = self.percent()?;
-        if self.token() == Some(&Token::Fraction) {
-            self.advance();
-            return Ok(Expr::UnaryOperationRight { expr: Box::new(expr), op: Token::Fraction })
+    /// an atom, number literals may be followed by a unit
+    pub fn operand(&mut self) -> ParseResult {
+        let expr = self.atom()?;
+        if !matches!(expr.kind, ExprKind::Int(_) | ExprKind::Float(_)) { return Ok(expr) }
+        let start = self.idx;
+        match self.unit(false)? {
+            Some(unit) => Ok(Expr::binary(expr, Expr::new(ExprKind::Unit(unit), self.span_from(start)), Token::Mult)),
+            None => Ok(expr)
         }
-        Ok(expr)
     }
-    pub fn percent(&mut self) -> ParseResult {
-        let expr = self.atom()?;
-        if self.token() == Some(&Token::Percent) {
+    /// unit symbols with integer powers, like `kg m^2`, only a conversion target may also use `*` and `/`
+    pub fn unit(&mut self, conversion: bool) -> Result<Option<String>, Error> {
+        let (mut numerator, mut denominator) = (vec![], vec![]);
+        let mut divide = false;
+        if conversion && self.token() == Some(&Token::Int(1.into())) && self.peek() == Some(&Token::Div) {
             self.advance();
-            return Ok(Expr::UnaryOperationRight { expr: Box::new(expr), op: Token::Percent })
+            self.advance();
+            divide = true;
         }
-        Ok(expr)
+        while let Some(Token::ID(id)) = self.token() {
+            if !self.is_unit(0) {
+                if conversion { return error!(Parse, "unknown unit `{id}`") }
+                break
+            }
+            let mut atom = id.clone();
+            self.advance();
+            if self.token() == Some(&Token::Power) {
+                self.advance();
+                let sign = if self.token() == Some(&Token::Sub) { self.advance(); "-" } else { "" };
+                let Some(Token::Int(exponent)) = self.token() else { return error!(Parse, "expected an integer as exponent of the unit `{atom}`") };
+                atom = format!("{atom}^{sign}{exponent}");
+                self.advance();
+            }
+            if divide { denominator.push(atom) } else { numerator.push(atom) }
+            // after a number, `/` only divides by a unit if one follows, `3 m / 2 s` is a division of quantities
+            if !conversion {
+                if !divide && self.token() == Some(&Token::Div) && self.is_unit(1) {
+                    self.advance();
+                    divide = true;
+                }
+                continue
+            }
+            match self.token() {
+                Some(Token::Mult) => self.advance(),
+                Some(Token::ID(_)) => {}
+                Some(Token::Div) if !divide => {
+                    self.advance();
+                    divide = true;
+                }
+                _ => break
+            }
+        }
+        if divide && denominator.is_empty() { return error!(Parse, "expected a unit after '{}'", Token::Div) }
+        Ok(match (numerator.is_empty(), denominator.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(numerator.join(" ")),
+            (true, false) => Some(format!("1/{}", denominator.join(" "))),
+            (false, false) => Some(format!("{}/{}", numerator.join(" "), denominator.join(" "))),
+        })
+    }
+    /// whether the token at `offset` from the current one is a unit symbol and not a call
+    pub fn is_unit(&self, offset: usize) -> bool {
+        let token = |offset| self.tokens.get(self.idx + offset).map(|(token, _)| token);
+        matches!(token(offset), Some(Token::ID(id)) if units::get(id).is_some()) && token(offset + 1) != Some(&Token::EvalIn)
     }
     pub fn atom(&mut self) -> ParseResult {
-        let res = match self.token() {
+        let start = self.idx;
+        let kind = match self.token() {
             Some(Token::EvalIn) => {
                 self.advance();
-                let expr = self.expr()?;
-                if self.token() != Some(&Token::EvalOut) || self.token() == Some(&Token::Seperator) {
+                let expr = self.element();
+                if !matches!(self.token(), Some(Token::EvalOut | Token::SetOut) | None) {
                     self.advance_if(self.token() == Some(&Token::Seperator));
                     let mut exprs = vec![expr];
                     while let Some(token) = self.token() {
-                        if token == &Token::EvalOut { break }
-                        exprs.push(self.expr()?);
+                        if matches!(token, Token::EvalOut | Token::SetOut) { break }
+                        exprs.push(self.element());
                         self.advance_if(self.token() == Some(&Token::Seperator));
                     }
-                    self.expect_token_advance(Token::EvalOut)?;
-                    return Ok(Expr::Vector(exprs))
+                    return Ok(self.close(Token::EvalOut, ExprKind::Vector(exprs), start))
                 }
-                Ok(expr)
+                // the brackets are not part of the expression
+                let span = expr.span;
+                let expr = self.close(Token::EvalOut, expr.kind, start);
+                return Ok(match expr.kind {
+                    ExprKind::Error => expr,
+                    kind => Expr::new(kind, span)
+                })
             }
             Some(Token::Pipe) => {
                 self.advance();
-                let expr = Box::new(self.expr()?);
-                self.expect_token_advance(Token::Pipe)?;
-                Ok(Expr::Absolute(expr))
+                let expr = Box::new(self.recover(|token| token == &Token::Pipe));
+                return Ok(self.close(Token::Pipe, ExprKind::Absolute(expr), start))
             }
             Some(Token::SetIn) => {
                 self.advance();
                 let mut exprs = vec![];
                 while let Some(token) = self.token() {
-                    if token == &Token::SetOut { break }
-                    exprs.push(self.expr()?);
+                    if matches!(token, Token::EvalOut | Token::SetOut) { break }
+                    exprs.push(self.element());
                     self.advance_if(self.token() == Some(&Token::Seperator));
                 }
-                self.expect_token_advance(Token::SetOut)?;
-                Ok(Expr::Set(exprs))
-            }
-            Some(Token::ID(id)) => Ok(Expr::ID(id.clone())),
-            Some(Token::Int(v)) => Ok(Expr::Int(*v)),
-            Some(Token::Float(v)) => Ok(Expr::Float(*v)),
-            Some(Token::Continue) => Ok(Expr::Continue),
-            Some(token) => error!("unexpected token '{token}'"),
-            None => error!("unexpected end of input")
+                return Ok(self.close(Token::SetOut, ExprKind::Set(exprs), start))
+            }
+            Some(Token::ID(id)) => {
+                let id = id.clone();
+                self.advance();
+                if self.token() != Some(&Token::EvalIn) { return Ok(Expr::new(ExprKind::ID(id), self.span_from(start))) }
+                self.advance();
+                let mut args = vec![];
+                while let Some(token) = self.token() {
+                    if matches!(token, Token::EvalOut | Token::SetOut) { break }
+                    args.push(self.element());
+                    self.advance_if(self.token() == Some(&Token::Seperator));
+                }
+                return Ok(self.close(Token::EvalOut, ExprKind::Call { id, args }, start))
+            }
+            Some(Token::Int(v)) => Ok(ExprKind::Int(v.clone())),
+            Some(Token::Float(v)) => Ok(ExprKind::Float(*v)),
+            Some(Token::Continue) => Ok(ExprKind::Continue),
+            // reported by the lexer
+            Some(Token::Error) => Ok(ExprKind::Error),
+            Some(token) => error!(Parse, "unexpected token '{token}'"),
+            None => error!(Parse, "unexpected end of input")
         };
-        if res.is_ok() { self.advance(); }
-        res
+        if kind.is_ok() { self.advance(); }
+        Ok(Expr::new(kind?, self.span_from(start)))
     }
 }
 
-pub fn parse(tokens: Vec<Token>) -> ParseResult {
+pub fn parse(tokens: Vec<(Token, Span)>) -> ParseResult {
     Parser::new(tokens).parse()
+}
+
+pub fn parse_all(tokens: Vec<(Token, Span)>) -> (Expr, Vec<Error>) {
+    Parser::new(tokens).parse_all()
 }
\ No newline at end of file
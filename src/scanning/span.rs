@@ -0,0 +1,19 @@
+/// a byte range of the input, an empty span is unknown
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+    /// from the start of `self` to the end of `other`, unknown if either is
+    pub fn to(&self, other: &Self) -> Self {
+        if self.is_empty() || other.is_empty() { return Self::default() }
+        Self::new(self.start.min(other.start), self.end.max(other.end))
+    }
+}
@@ -1,15 +1,26 @@
 use std::fmt::Display;
 
+use num_bigint::BigInt;
+
 use crate::logos::Logos;
 
+/// an integer literal with a two character base prefix like `0x`
+fn radix(slice: &str, radix: u32) -> Option<BigInt> {
+    BigInt::parse_bytes(slice[2..].replace('_', "").as_bytes(), radix)
+}
+
 #[derive(Logos, Debug, Clone, PartialEq)]
 pub enum Token {
 
     #[regex(r"[a-zA-Z_]([a-zA-Z_0-9])*", |lex| lex.slice().to_string())]
     ID(String),
-    #[regex(r"[0-9]+", |lex| lex.slice().parse())]
-    Int(i64),
-    #[regex(r"\.[0-9]+|[0-9]+\.[0-9]+", |lex| lex.slice().parse())]
+    #[regex(r"[0-9][0-9_]*", |lex| lex.slice().replace('_', "").parse())]
+    #[regex(r"0x[0-9a-fA-F_]+", |lex| radix(lex.slice(), 16))]
+    #[regex(r"0o[0-7_]+", |lex| radix(lex.slice(), 8))]
+    #[regex(r"0b[01_]+", |lex| radix(lex.slice(), 2))]
+    Int(BigInt),
+    #[regex(r"\.[0-9][0-9_]*|[0-9][0-9_]*\.[0-9][0-9_]*", |lex| lex.slice().replace('_', "").parse())]
+    #[regex(r"(\.[0-9][0-9_]*|[0-9][0-9_]*(\.[0-9][0-9_]*)?)[eE][+-]?[0-9]+", |lex| lex.slice().replace('_', "").parse())]
     Float(f64),
 
     #[token("[")]
@@ -84,8 +95,6 @@ impl Display for Token {
             Self::EvalOut => write!(f, ")"),
             Self::SetIn => write!(f, "{{"),
             Self::SetOut => write!(f, "}}"),
-            Self::SetIn => write!(f, "{{"),
-            Self::SetOut => write!(f, "}}"),
             Self::Seperator => write!(f, ","),
             Self::Represent => write!(f, ":"),
             Self::Address => write!(f, "@"),
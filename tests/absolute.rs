@@ -0,0 +1,21 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn absolute_value_of_numbers() {
+    assert_eq!(eval("|-3|"), "3");
+    assert_eq!(eval("|1/3 - 1|"), "2/3");
+    assert_eq!(eval("|-2 m|"), "2 m");
+}
+
+#[test]
+fn norm_of_vectors_and_size_of_sets() {
+    assert_eq!(eval("|(3, 4)|"), "5");
+    assert_eq!(eval("|{1, 1, 2}|"), "2");
+}
+
+#[test]
+fn symbolic_absolute_value_is_kept() {
+    assert_eq!(eval("|y - 1|"), "| (y - 1) |");
+}
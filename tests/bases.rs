@@ -0,0 +1,22 @@
+mod common;
+
+use common::{errors, eval};
+use vac::ErrorKind;
+
+#[test]
+fn literals_in_other_bases() {
+    assert_eq!(eval("0x1F"), "31");
+    assert_eq!(eval("0b1010"), "10");
+    assert_eq!(eval("0o17"), "15");
+    assert_eq!(eval("1_000_000"), "1000000");
+    assert_eq!(eval("1e-9 * 1e9"), "1");
+}
+
+#[test]
+fn conversion_to_other_bases() {
+    assert_eq!(eval("255 -> hex"), "0xFF");
+    assert_eq!(eval("10 -> bin"), "0b1010");
+    assert_eq!(eval("-10 -> hex"), "-0xA");
+    assert_eq!(eval("0xFF -> dec"), "255");
+    assert_eq!(errors("1.5 -> hex"), vec![(ErrorKind::TypeMismatch, 0)]);
+}
@@ -0,0 +1,34 @@
+mod common;
+
+use common::eval_with;
+use vac::{Engine, ErrorKind};
+
+#[test]
+fn later_bindings_apply_to_stored_expressions() {
+    let mut engine = Engine::new().unwrap();
+    eval_with(&mut engine, "q <- w + 1");
+    assert_eq!(eval_with(&mut engine, "q"), "(w + 1)");
+    eval_with(&mut engine, "w <- 2");
+    assert_eq!(eval_with(&mut engine, "q"), "3");
+    assert_eq!(eval_with(&mut engine, "q + 0"), "3");
+    assert_eq!(eval_with(&mut engine, "num(q)"), "3");
+}
+
+#[test]
+fn symbolic_unknowns_shadow_bindings() {
+    let mut engine = Engine::new().unwrap();
+    eval_with(&mut engine, "q <- w ^ 2");
+    eval_with(&mut engine, "w <- 3");
+    assert_eq!(eval_with(&mut engine, "q"), "9");
+    // the derivative is taken symbolically and evaluated at the binding
+    assert_eq!(eval_with(&mut engine, "diff(q, w)"), "6");
+}
+
+#[test]
+fn cyclic_bindings_are_an_error() {
+    let mut engine = Engine::new().unwrap();
+    eval_with(&mut engine, "z <- k * 2");
+    eval_with(&mut engine, "k <- z");
+    let errors = engine.eval_str("k").unwrap_err();
+    assert_eq!(errors[0].kind, ErrorKind::Limit);
+}
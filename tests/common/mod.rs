@@ -0,0 +1,20 @@
+// every test crate uses only some of the helpers
+#![allow(dead_code)]
+
+use vac::{Engine, ErrorKind};
+
+/// a result as the REPL prints it, in a new engine
+pub fn eval(input: &str) -> String {
+    eval_with(&mut Engine::new().unwrap(), input)
+}
+
+pub fn eval_with(engine: &mut Engine, input: &str) -> String {
+    let ret = engine.eval_str(input).unwrap();
+    engine.display(&ret)
+}
+
+/// the kind and position of every error of an input
+pub fn errors(input: &str) -> Vec<(ErrorKind, usize)> {
+    let mut engine = Engine::new().unwrap();
+    engine.eval_str(input).unwrap_err().iter().map(|e| (e.kind, e.span.unwrap().start)).collect()
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::{errors, eval};
+use vac::ErrorKind;
+
+#[test]
+fn vectors_compare_element_wise() {
+    assert_eq!(eval("(1, 2, 3) = (1, 2, 3)"), "( true true true )");
+    assert_eq!(eval("(1, 2, 3) < (2, 2, 2)"), "( true false false )");
+    assert_eq!(eval("(1, 2, 3) >= 2"), "( false true true )");
+    assert_eq!(eval("~((1, 2) = (1, 3))"), "( false true )");
+}
+
+#[test]
+fn vectors_combine_element_wise() {
+    assert_eq!(eval("(1, 2) + (3, 4)"), "( 4 6 )");
+    assert_eq!(eval("(1, 2) * 3"), "( 3 6 )");
+    assert_eq!(errors("(1, 2) + (1, 2, 3)"), vec![(ErrorKind::TypeMismatch, 0)]);
+}
+
+#[test]
+fn comparisons_do_not_chain() {
+    assert_eq!(errors("1 < 2 < 3"), vec![(ErrorKind::Parse, 6)]);
+    assert_eq!(eval("(1 < 2) = (2 < 3)"), "true");
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn imaginary_unit() {
+    assert_eq!(eval("i^2"), "-1");
+    assert_eq!(eval("sqrt(-4)"), "2i");
+    assert_eq!(eval("(1 + 2*i) * (3 - i)"), "5 + 5i");
+    assert_eq!(eval("|3 + 4*i|"), "5");
+    assert_eq!(eval("conj(1 + i)"), "1 - i");
+}
+
+#[test]
+fn negative_bases_have_complex_powers() {
+    assert_eq!(eval("(-1)^0.5"), "i");
+    assert_eq!(eval("(-4)^(1/2)"), "2i");
+    assert_eq!(eval("(-1)^(1/2) = i"), "true");
+    assert_eq!(eval("(-8)^(1/3)"), "1 + 1.73205080756888i");
+    assert_eq!(eval("(-2)^3"), "-8");
+}
@@ -0,0 +1,22 @@
+mod common;
+
+use common::{eval, eval_with};
+use vac::Engine;
+
+#[test]
+fn named_constants_are_numbers() {
+    assert_eq!(eval("pi"), "3.14159265358979");
+    assert_eq!(eval("tau / pi"), "2");
+    assert_eq!(eval("ln(e)"), "1");
+    assert_eq!(eval("phi^2 - phi"), "1");
+    assert_eq!(eval("inf"), "inf");
+    assert_eq!(eval("nan"), "NaN");
+}
+
+#[test]
+fn constants_can_stay_symbolic() {
+    let mut engine = Engine::new().unwrap();
+    engine.command("symbolic on").unwrap();
+    assert_eq!(eval_with(&mut engine, "2 * pi"), "(2 * pi)");
+    assert_eq!(eval_with(&mut engine, "num(2 * pi)"), "6.28318530717959");
+}
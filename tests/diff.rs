@@ -0,0 +1,29 @@
+mod common;
+
+use common::eval;
+use vac::Engine;
+
+#[test]
+fn polygamma_values() {
+    assert_eq!(eval("trigamma(1)"), "1.64493406684823");
+    assert_eq!(eval("polygamma(2, 1)"), "-2.40411380631919");
+    assert_eq!(eval("trigamma(-0.5)"), "8.93480220054468");
+}
+
+#[test]
+fn digamma_and_its_derivatives_are_differentiable() {
+    assert_eq!(eval("diff(diff(gamma(y), y), y)"), "((gamma(y) * (digamma(y) * digamma(y))) + (gamma(y) * trigamma(y)))");
+    assert_eq!(eval("diff(trigamma(y), y)"), "polygamma(2, y)");
+    assert_eq!(eval("diff(polygamma(2, y), y)"), "polygamma(3, y)");
+}
+
+#[test]
+fn complex_parts_are_differentiable() {
+    assert_eq!(eval("diff(re(y^2), y)"), "re((2 * y))");
+    assert_eq!(eval("diff(im(i*y), y)"), "1");
+    assert_eq!(eval("diff(conj(y), y)"), "1");
+    let mut engine = Engine::new().unwrap();
+    engine.set_var("y", 2);
+    let ret = engine.eval_str("diff(arg(y), y)").unwrap();
+    assert_eq!(engine.display(&ret), "0");
+}
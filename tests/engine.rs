@@ -0,0 +1,64 @@
+mod common;
+
+use common::eval_with;
+use vac::{Engine, ErrorKind, Return, Value};
+
+#[test]
+fn engine_moves_to_another_thread() {
+    let mut engine = Engine::new().unwrap();
+    engine.register_function("first", |args| Ok(args[0].clone()));
+    let result = std::thread::spawn(move || engine.eval_number("first(2) + 1")).join().unwrap();
+    assert_eq!(result, Ok(3.));
+}
+
+#[test]
+fn variables() {
+    let mut engine = Engine::new().unwrap();
+    assert_eq!(engine.get_var("r"), None);
+    engine.set_var("r", 2);
+    assert_eq!(engine.get_var("r"), Some(&Return::Value(Value::integer(2))));
+    assert_eq!(eval_with(&mut engine, "r^2 + 1"), "5");
+    eval_with(&mut engine, "s <- r * 3");
+    assert_eq!(engine.get_var("s"), Some(&Return::Value(Value::integer(6))));
+}
+
+#[test]
+fn registered_functions() {
+    let mut engine = Engine::new().unwrap();
+    engine.register_function("double", |args| match args {
+        [value] => value.number().map(|x| Value::from(2. * x)).ok_or_else(|| vac::Error::new(ErrorKind::TypeMismatch, "expected a number".to_string())),
+        _ => Err(vac::Error::new(ErrorKind::Arity, format!("expected 1 argument for `double`, got {}", args.len())))
+    });
+    assert_eq!(engine.eval_number("double(3) + 1"), Ok(7.));
+    assert_eq!(eval_with(&mut engine, "double(y)"), "double(y)");
+    engine.set_var("y", 4);
+    assert_eq!(eval_with(&mut engine, "double(y)"), "8");
+    assert_eq!(engine.eval_number("double(1, 2)").unwrap_err()[0].kind, ErrorKind::Arity);
+}
+
+#[test]
+fn numbers() {
+    let mut engine = Engine::new().unwrap();
+    assert_eq!(engine.eval_number("1/4 + 1"), Ok(1.25));
+    assert_eq!(engine.eval_number("[1, 2]").unwrap_err()[0].kind, ErrorKind::TypeMismatch);
+    assert_eq!(engine.eval_number("1 + * 2").unwrap_err()[0].kind, ErrorKind::Parse);
+}
+
+#[test]
+fn commands() {
+    let mut engine = Engine::new().unwrap();
+    assert_eq!(engine.command("symbolic off"), Ok(None));
+    assert_eq!(engine.command("symbolic"), Ok(Some("symbolic off".to_string())));
+    assert_eq!(engine.command("format sci 3"), Ok(None));
+    assert_eq!(eval_with(&mut engine, "1234.5"), "1.23e3");
+    assert_eq!(engine.command("format"), Ok(Some("format sci 3, separators off".to_string())));
+    assert_eq!(engine.command("frobnicate").unwrap_err().kind, ErrorKind::Command);
+}
+
+#[test]
+fn symbolic_results_use_the_format() {
+    let mut engine = Engine::new().unwrap();
+    assert_eq!(eval_with(&mut engine, "0.1 + 0.2 + y"), "(0.3 + y)");
+    engine.command("format fix 2").unwrap();
+    assert_eq!(eval_with(&mut engine, "y * 1.5"), "(y * 1.50)");
+}
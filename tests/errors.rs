@@ -0,0 +1,36 @@
+mod common;
+
+use common::errors;
+use vac::{Engine, Error, ErrorKind};
+
+#[test]
+fn every_syntax_error_is_reported() {
+    assert_eq!(errors("1 + * 2 + * 3"), vec![(ErrorKind::Parse, 4), (ErrorKind::Parse, 10)]);
+    assert_eq!(errors("(1 + *, 2 + *)"), vec![(ErrorKind::Parse, 5), (ErrorKind::Parse, 12)]);
+    assert_eq!(errors("|1 + *| + 2 2"), vec![(ErrorKind::Parse, 5), (ErrorKind::Parse, 12)]);
+    assert_eq!(errors("1 + , 2 +"), vec![(ErrorKind::Parse, 4), (ErrorKind::Parse, 9)]);
+    assert_eq!(errors("|3 + 4i|"), vec![(ErrorKind::Parse, 6)]);
+}
+
+#[test]
+fn rest_of_a_broken_input_is_checked() {
+    assert_eq!(errors("1 + * 2 + sqrt(1, 2)"), vec![(ErrorKind::Parse, 4), (ErrorKind::Arity, 10)]);
+    assert_eq!(errors("sqrt(1 + *, 2)"), vec![(ErrorKind::Arity, 0), (ErrorKind::Parse, 9)]);
+}
+
+#[test]
+fn calls_cut_short_are_not_checked() {
+    assert_eq!(errors("sqrt("), vec![(ErrorKind::Parse, 5)]);
+    assert_eq!(errors("atan2(1, 2 + *"), vec![(ErrorKind::Parse, 13), (ErrorKind::Parse, 14)]);
+    assert_eq!(errors("(1, 2"), vec![(ErrorKind::Parse, 5)]);
+}
+
+#[test]
+fn broken_input_is_not_run() {
+    let mut engine = Engine::new().unwrap();
+    assert!(engine.eval_str("x <- 2 + * 3").is_err());
+    assert_eq!(engine.get_var("x"), None);
+    engine.register_function("fail", |_| Err(Error::new(ErrorKind::Domain, "called".to_string())));
+    assert_eq!(engine.eval_str("fail(1) + * 2").unwrap_err().len(), 1);
+    assert_eq!(engine.eval_str("sum(fail(k), k, 1, 3) + * 2").unwrap_err().len(), 1);
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use common::{errors, eval};
+use vac::{Engine, ErrorKind};
+
+#[test]
+fn factorial_of_integers_is_exact() {
+    assert_eq!(eval("5!"), "120");
+    assert_eq!(eval("0!"), "1");
+    assert_eq!(eval("25!"), "15511210043330985984000000");
+}
+
+#[test]
+fn factorial_of_reals_is_the_gamma_function() {
+    let half = Engine::new().unwrap().eval_number("0.5!").unwrap();
+    assert!((half - std::f64::consts::PI.sqrt() / 2.).abs() < 1e-12);
+    assert_eq!(eval("gamma(5)"), "24");
+}
+
+#[test]
+fn factorial_broadcasts_and_rejects_negative_integers() {
+    assert_eq!(eval("(1, 2, 3)!"), "( 1 2 6 )");
+    assert_eq!(errors("(-1)!"), vec![(ErrorKind::Domain, 0)]);
+}
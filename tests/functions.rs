@@ -0,0 +1,30 @@
+mod common;
+
+use common::eval_with;
+use vac::{Engine, ErrorKind};
+
+#[test]
+fn defined_functions_are_called() {
+    let mut engine = Engine::new().unwrap();
+    eval_with(&mut engine, "f(x, y) <- x^2 + y");
+    assert_eq!(eval_with(&mut engine, "f(2, 3)"), "7");
+    eval_with(&mut engine, "g(x) <- x + 1");
+    assert_eq!(eval_with(&mut engine, "g(g(1))"), "3");
+}
+
+#[test]
+fn parameters_are_local() {
+    let mut engine = Engine::new().unwrap();
+    eval_with(&mut engine, "x <- 10");
+    eval_with(&mut engine, "f(x) <- 2 * x");
+    assert_eq!(eval_with(&mut engine, "f(3) + x"), "16");
+}
+
+#[test]
+fn calls_with_symbols_stay_symbolic() {
+    let mut engine = Engine::new().unwrap();
+    eval_with(&mut engine, "f(x, y) <- x^2 + y");
+    assert_eq!(eval_with(&mut engine, "f(a, 1)"), "((a ^ 2) + 1)");
+    assert_eq!(eval_with(&mut engine, "h(2)"), "h(2)");
+    assert_eq!(engine.eval_str("f(1)").unwrap_err()[0].kind, ErrorKind::Arity);
+}
@@ -0,0 +1,12 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn gcd_and_lcm_are_exact() {
+    assert_eq!(eval("gcd(10^30, 10^20)"), "100000000000000000000");
+    assert_eq!(eval("lcm(4294967311, 4294967357)"), "18446744400127067027");
+    assert_eq!(eval("gcd(12, 18, 8)"), "2");
+    assert_eq!(eval("lcm((4, 6))"), "12");
+    assert_eq!(eval("gcd(-4, 6)"), "2");
+}
@@ -0,0 +1,16 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn plus_minus_gives_both_branches() {
+    assert_eq!(eval("1 +- 2"), "{ -1 3 }");
+    assert_eq!(eval("1 +- 0"), "1");
+}
+
+#[test]
+fn branches_propagate() {
+    assert_eq!(eval("(1 +- 1) * 2"), "{ 0 4 }");
+    assert_eq!(eval("(-3 +- sqrt(9 - 8)) / 2"), "{ -2 -1 }");
+    assert_eq!(eval("(1 +- 1) + (10 +- 10)"), "{ 0 2 20 22 }");
+}
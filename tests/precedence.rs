@@ -0,0 +1,67 @@
+mod common;
+
+use common::{eval, eval_with};
+use vac::{Engine, ErrorKind};
+
+#[test]
+fn power_is_right_associative() {
+    assert_eq!(eval("a^b^c"), "(a ^ (b ^ c))");
+    assert_eq!(eval("2^3^2"), "512");
+    assert_eq!(eval("(2^3)^2"), "64");
+}
+
+#[test]
+fn unary_minus_binds_weaker_than_power() {
+    assert_eq!(eval("-a^2"), "(- (a ^ 2))");
+    assert_eq!(eval("-2^2"), "-4");
+    assert_eq!(eval("(-2)^2"), "4");
+}
+
+#[test]
+fn unary_minus_in_exponent() {
+    assert_eq!(eval("a^-b^c"), "(a ^ (- (b ^ c)))");
+    assert_eq!(eval("2^-1"), "1/2");
+    assert_eq!(eval("2 * -3"), "-6");
+}
+
+#[test]
+fn arithmetic_is_left_associative() {
+    assert_eq!(eval("1 - 2 - 3"), "-4");
+    assert_eq!(eval("12 / 2 / 3"), "2");
+    assert_eq!(eval("2 - 3 + 4"), "3");
+    assert_eq!(eval("1 + 2 * 3"), "7");
+    assert_eq!(eval("2 * 3^2"), "18");
+}
+
+#[test]
+fn postfix_binds_tightest() {
+    assert_eq!(eval("-3!"), "-6");
+    assert_eq!(eval("2^3!"), "64");
+    assert_eq!(eval("3!!"), "720");
+}
+
+#[test]
+fn comparison_and_not() {
+    assert_eq!(eval("a + b * c = d"), "((a + (b * c)) = d)");
+    assert_eq!(eval("1 + 2 * 3 = 7"), "true");
+    assert_eq!(eval("~a = b"), "(~ (a = b))");
+    assert_eq!(eval("~1 = 2"), "true");
+}
+
+#[test]
+fn range_does_not_chain() {
+    assert_eq!(eval("a + 1 ... b * 2"), "((a + 1) ... (b * 2))");
+    let mut engine = Engine::new().unwrap();
+    let errors = engine.eval_str("1 ... 3 ... 5").unwrap_err();
+    assert_eq!(errors[0].kind, ErrorKind::Parse);
+}
+
+#[test]
+fn store_and_conversion_bind_weakest() {
+    let mut engine = Engine::new().unwrap();
+    eval_with(&mut engine, "x <- a + b");
+    assert_eq!(eval_with(&mut engine, "x"), "(a + b)");
+    assert_eq!(eval("1 km + 1 m -> m"), "1001 m");
+    eval_with(&mut engine, "x <- 3 m -> cm");
+    assert_eq!(eval_with(&mut engine, "x"), "300 cm");
+}
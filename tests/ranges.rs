@@ -0,0 +1,18 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn ranges_infer_their_step() {
+    assert_eq!(eval("1 ... 5"), "( 1 2 3 4 5 )");
+    assert_eq!(eval("5 ... 3"), "( 5 4 3 )");
+    assert_eq!(eval("(1, 2, ..., 5)"), "( 1 2 3 4 5 )");
+    let odd = (1..100).step_by(2).map(|n| n.to_string()).collect::<Vec<String>>().join(" ");
+    assert_eq!(eval("{1, 3, ..., 99}"), format!("{{ {odd} }}"));
+}
+
+#[test]
+fn large_sets_are_built_in_linear_time() {
+    assert_eq!(eval("|{1, 2, ..., 100000}|"), "100000");
+    assert_eq!(eval("|{1, 1, 2, 2, 1/2, 2/4}|"), "3");
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn division_stays_exact() {
+    assert_eq!(eval("1/3*3"), "1");
+    assert_eq!(eval("1/3 + 1/6"), "1/2");
+    assert_eq!(eval("(2/3)^2"), "4/9");
+}
+
+#[test]
+fn negative_integer_powers_are_fractions() {
+    assert_eq!(eval("2^-1"), "1/2");
+    assert_eq!(eval("(1/2)^-2"), "4");
+}
+
+#[test]
+fn floats_make_the_result_inexact() {
+    assert_eq!(eval("0.5 + 1/3"), "0.833333333333333");
+}
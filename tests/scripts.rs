@@ -0,0 +1,27 @@
+use std::process::Command;
+
+/// runs `vac` on a script with the given lines
+fn run(name: &str, source: &str) -> (bool, String, String) {
+    let path = std::env::temp_dir().join(format!("vac-{}-{name}.vac", std::process::id()));
+    std::fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_vac")).arg(&path).output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr).replace(&path.display().to_string(), "script");
+    (output.status.success(), String::from_utf8_lossy(&output.stdout).to_string(), stderr)
+}
+
+#[test]
+fn every_line_is_evaluated() {
+    let (ok, stdout, stderr) = run("lines", "x <- 2\nx * 3\n\nx + 1\n");
+    assert!(ok);
+    assert_eq!(stdout, "6\n3\n");
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn errors_have_the_file_and_line() {
+    let (ok, stdout, stderr) = run("errors", "1 + 1\n1 + * 2\n2 + 2\n");
+    assert!(!ok);
+    assert_eq!(stdout, "2\n4\n");
+    assert!(stderr.starts_with("script:2:5: unexpected token '*'"), "{stderr}");
+}
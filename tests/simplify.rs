@@ -0,0 +1,40 @@
+mod common;
+
+use common::{eval, eval_with};
+use vac::Engine;
+
+#[test]
+fn constants_are_brought_together() {
+    assert_eq!(eval("2 + y + 3"), "(y + 5)");
+    assert_eq!(eval("y + 2 + 3"), "(y + 5)");
+    assert_eq!(eval("2 * y * 3"), "(y * 6)");
+    assert_eq!(eval("2 + y - 3"), "(y - 1)");
+    assert_eq!(eval("2 - y + 3"), "(5 - y)");
+}
+
+#[test]
+fn double_negation_is_removed() {
+    assert_eq!(eval("y - 2 + 3"), "(y + 1)");
+    assert_eq!(eval("y - -z"), "(y + z)");
+}
+
+#[test]
+fn symbols_are_not_constants() {
+    assert_eq!(eval("x + y + z"), "(x + (y + z))");
+}
+
+#[test]
+fn named_constants_in_rules_are_literals() {
+    let mut engine = Engine::new().unwrap();
+    engine.command("symbolic on").unwrap();
+    assert_eq!(eval_with(&mut engine, "sin(pi)"), "0");
+    assert_eq!(eval_with(&mut engine, "sin(y)"), "sin(y)");
+    assert_eq!(eval_with(&mut engine, "ln(e) + y"), "(1 + y)");
+    assert_eq!(eval_with(&mut engine, "2 * pi * 3"), "(pi * 6)");
+}
+
+#[test]
+fn constant_rule_variables_need_constants() {
+    assert_eq!(eval("2 * y * z"), "(2 * (y * z))");
+    assert_eq!(eval("2 * y * (3 + z)"), "(2 * (y * (3 + z)))");
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::{eval, eval_with};
+use vac::Engine;
+
+#[test]
+fn linear_equations_are_exact() {
+    assert_eq!(eval("solve(2*x + 3 = 11, x)"), "{ 4 }");
+    assert_eq!(eval("solve(3*x = 1, x)"), "{ 1/3 }");
+}
+
+#[test]
+fn quadratic_equations_are_exact() {
+    assert_eq!(eval("solve(x^2 = 4, x)"), "{ -2 2 }");
+    assert_eq!(eval("solve(x^2 + 1 = 0, x)"), "{ -i i }");
+    assert_eq!(eval("x^2 = 4"), "{ -2 2 }");
+}
+
+#[test]
+fn other_equations_are_solved_numerically() {
+    assert_eq!(eval("solve(x^3 = 2 + x, x)"), "{ 1.52137970680457 }");
+}
+
+#[test]
+fn symbolic_solutions_are_sets() {
+    let mut engine = Engine::new().unwrap();
+    engine.command("symbolic on").unwrap();
+    assert_eq!(eval_with(&mut engine, "solve(x = pi)"), "{ pi }");
+    assert_eq!(eval_with(&mut engine, "solve(x^2 = pi, x)"), "{ ((- sqrt((4 * pi))) / 2) (sqrt((4 * pi)) / 2) }");
+    assert_eq!(eval("solve(a*x + b = 0, x)"), "{ ((- b) / a) }");
+}
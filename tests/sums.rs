@@ -0,0 +1,22 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn sums_and_products_over_bounds() {
+    assert_eq!(eval("sum(k^2, k, 1, 10)"), "385");
+    assert_eq!(eval("sum(1/k, k, 1, 4)"), "25/12");
+    assert_eq!(eval("prod(k, k, 1, 5)"), "120");
+}
+
+#[test]
+fn sums_over_ranges_and_vectors() {
+    assert_eq!(eval("sum(k, k, 1 ... 100)"), "5050");
+    assert_eq!(eval("sum(k, k, (1, 3, 5))"), "9");
+}
+
+#[test]
+fn integration_is_numeric() {
+    assert_eq!(eval("integrate(x^2, x, 0, 3)"), "9");
+    assert_eq!(eval("integrate(sin(x), x, 0, pi)"), "2");
+}
@@ -0,0 +1,25 @@
+mod common;
+
+use common::eval;
+
+#[test]
+fn units_after_a_number_can_be_divided() {
+    assert_eq!(eval("3 m/s"), "3 m/s");
+    assert_eq!(eval("9.81 m/s^2"), "9.81 m/s^2");
+    assert_eq!(eval("5 km/h -> m/s"), "1.38888888888889 m/s");
+    assert_eq!(eval("1 J/kg K"), "1 J/kg K");
+}
+
+#[test]
+fn quantities_divide_by_quantities() {
+    assert_eq!(eval("3 m / 2 s"), "3/2 m/s");
+    assert_eq!(eval("6 m / 2"), "3 m");
+}
+
+#[test]
+fn sums_in_different_units_are_approximate() {
+    assert_eq!(eval("1 ft + 1 m"), "4.28083989501312 ft");
+    assert_eq!(eval("1 km + 500 m"), "1.5 km");
+    assert_eq!(eval("1 m + 2 m"), "3 m");
+    assert_eq!(eval("1 m/s + 1 km/h"), "1.27777777777778 m/s");
+}